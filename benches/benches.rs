@@ -42,6 +42,14 @@ fn benchmark_random_data(c: &mut Criterion) {
             });
         });
 
+        // Build with --features simd on nightly to compare the vectorized version.
+        group.bench_with_input(BenchmarkId::new("see_slice", size), size, |b, _| {
+            b.iter(|| {
+                let mut top = Top16::new(0);
+                top.see_slice(black_box(&data));
+            });
+        });
+
         group.bench_with_input(BenchmarkId::new("topset", size), size, |b, _| {
             b.iter(|| {
                 let mut top = TopSet::new(16, |a: &u32, b: &u32| b < a);
@@ -87,5 +95,88 @@ fn benchmark_worst_case(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, benchmark_random_data, benchmark_worst_case);
+fn benchmark_mostly_rejected(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mostly_rejected");
+    group.sample_size(10);
+
+    // About 20 of the million values are above the cutoff.
+    let size = 1_000_000;
+    let data = black_box(generate_random_data(size, 42));
+    let cutoff = 1_000_000_000 - 20_000;
+
+    group.bench_function("see", |b| {
+        b.iter(|| {
+            let mut top = Top16::new(cutoff);
+            for &value in &data {
+                top.see(value);
+            }
+            top
+        });
+    });
+
+    group.bench_function("see_all", |b| {
+        b.iter(|| {
+            let mut top = Top16::new(cutoff);
+            top.see_all(data.iter().copied());
+            top
+        });
+    });
+
+    group.finish();
+}
+
+fn benchmark_merge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merge_full");
+
+    // Two full Top16s whose values interleave, so about half of the other's make it in.
+    let a = Top16::from(&generate_random_data(1000, 1)[..]);
+    let b = Top16::from(&generate_random_data(1000, 2)[..]);
+
+    group.bench_function("merge", |bench| {
+        bench.iter(|| black_box(a).merged(black_box(&b)));
+    });
+
+    group.bench_function("merge_sorted", |bench| {
+        bench.iter(|| {
+            let mut top = black_box(a);
+            top.merge_sorted(black_box(&b));
+            top
+        });
+    });
+
+    group.finish();
+}
+
+fn benchmark_collect(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collect");
+
+    let top = Top16::from(&generate_random_data(1000, 42)[..]);
+
+    group.bench_function("iter_collect", |b| {
+        b.iter(|| black_box(&top).iter().collect::<Vec<u32>>());
+    });
+
+    group.bench_function("to_sorted_vec", |b| {
+        b.iter(|| black_box(&top).to_sorted_vec());
+    });
+
+    group.bench_function("copy_sorted_into", |b| {
+        let mut out = [0; 16];
+        b.iter(|| {
+            black_box(&top).copy_sorted_into(&mut out);
+            out
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_random_data,
+    benchmark_worst_case,
+    benchmark_mostly_rejected,
+    benchmark_merge,
+    benchmark_collect
+);
 criterion_main!(benches);
@@ -0,0 +1,183 @@
+//! TopFewBand keeps track of the top 16 values seen so far that are below
+//! an upper bound, as well as above the cutoff, e.g. the largest latencies
+//! that are still under a timeout.
+//!
+//! A value equal to either bound is not admitted.
+//! The upper bound is checked before the TopFew sees a value, so a TopFew
+//! without one keeps its single compare and branch for rejecting a value.
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+use crate::ixs::{Cap, Capacity};
+use crate::order::{Max, Order};
+use crate::top16::{Iter, TopFew};
+
+/// The top 16 values seen that are between two bounds.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewBand<T = u32, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
+    // The values, all of which are below the ceiling.
+    top: TopFew<T, N, O>,
+    // A value must be smaller than this to be admitted.
+    ceiling: T,
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> TopFewBand<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns a new instance of TopFewBand that only considers values that are larger
+    /// than low (the cutoff) and smaller than high.
+    pub fn new(low: T, high: T) -> Self {
+        Self {
+            top: TopFew::new(low),
+            ceiling: high,
+        }
+    }
+
+    /// Returns the current cutoff value, i.e. the lower bound.
+    #[inline]
+    pub fn cutoff(&self) -> T {
+        self.top.cutoff()
+    }
+
+    /// Returns the upper bound.
+    #[inline]
+    pub fn ceiling(&self) -> T {
+        self.ceiling
+    }
+
+    /// Returns the current threshold, as for TopFew::threshold().
+    #[inline]
+    pub fn threshold(&self) -> T {
+        self.top.threshold()
+    }
+
+    /// Returns the number of retained values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.top.len()
+    }
+
+    /// Returns true if no values are retained.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.top.is_empty()
+    }
+
+    /// Returns the largest retained value.
+    #[inline]
+    pub fn max(&self) -> Option<T> {
+        self.top.max()
+    }
+
+    /// Returns true if the value is one of the retained top values.
+    #[inline]
+    pub fn contains(&self, value: T) -> bool {
+        self.top.contains(value)
+    }
+
+    /// Returns true if seeing the value now would add it,
+    /// i.e. it is below the ceiling and the threshold lets it in.
+    #[inline]
+    pub fn would_admit(&self, value: T) -> bool {
+        O::gt(&self.ceiling, &value) && self.top.would_admit(value)
+    }
+
+    /// Considers a new value, as TopFew::rank() does, and returns its position,
+    /// or 0 if it was not admitted, which includes it not being below the ceiling.
+    #[inline]
+    pub fn rank(&mut self, value: T) -> usize {
+        if !O::gt(&self.ceiling, &value) {
+            // It still counts as seen, e.g. for max_ever().
+            self.top.note_seen(value);
+            return 0;
+        }
+        self.top.rank(value)
+    }
+
+    /// Considers a new value, and adds it if it is one of the top 16 values in the band.
+    #[inline]
+    pub fn see(&mut self, value: T) {
+        self.rank(value);
+    }
+
+    /// Considers each of the values in turn, as see() does, and returns
+    /// the number of them that were admitted.
+    pub fn see_all<I: IntoIterator<Item = T>>(&mut self, values: I) -> usize {
+        values
+            .into_iter()
+            .map(|v| usize::from(self.rank(v) != 0))
+            .sum()
+    }
+
+    /// Considers each of the TopFew's retained values, as see() does,
+    /// so that this one ends up with the top 16 values in the band that both have seen.
+    /// For another TopFewBand, pass its as_top_few().
+    pub fn merge(&mut self, other: &TopFew<T, N, O>) {
+        self.top.note_max_ever_of(other);
+        // The other's values come in descending order, so we skip the ones
+        // that are too large, and then once one of them is not admitted,
+        // none of the rest will be either.
+        let ceiling = self.ceiling;
+        for value in other.iter().skip_while(|v| O::ge(v, &ceiling)) {
+            if !self.top.would_admit(value) {
+                break;
+            }
+            self.top.see(value);
+        }
+    }
+
+    /// Clears out all of the values and sets a new cutoff, keeping the ceiling,
+    /// so this is like calling new() again with the same upper bound.
+    pub fn reinit(&mut self, low: T) {
+        self.top.reinit(low);
+    }
+
+    /// Returns an Iterator over the retained values, in descending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, N, O> {
+        self.top.iter()
+    }
+
+    /// Returns the TopFew that holds the values, e.g. for its other read-only methods.
+    #[inline]
+    pub fn as_top_few(&self) -> &TopFew<T, N, O> {
+        &self.top
+    }
+
+    /// Checks the invariants of the TopFew, as TopFew::check_invariants() does,
+    /// and also that every retained value is below the ceiling.
+    #[cfg(feature = "alloc")]
+    pub fn check_invariants(&self) -> Result<(), String>
+    where
+        T: core::fmt::Debug,
+    {
+        self.top.check_invariants()?;
+        match self.top.max() {
+            Some(max) if O::ge(&max, &self.ceiling) => Err(format!(
+                "element {max:?} is not below the ceiling {:?}",
+                self.ceiling
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<const N: usize, O: Order<Direction = Max>> TopFewBand<u32, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns the smallest value that would be admitted if it were seen now,
+    /// as TopFew::next_admission_value() does, or None if that is not below
+    /// the ceiling, since then no value can be admitted until the TopFewBand
+    /// is reinitialized.
+    #[inline]
+    pub fn next_admission_value(&self) -> Option<u32> {
+        let next = self.top.next_admission_value();
+        self.would_admit(next).then_some(next)
+    }
+}
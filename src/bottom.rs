@@ -0,0 +1,25 @@
+//! BottomFew keeps track of the bottom (smallest) 16 values seen so far.
+//!
+//! It is the mirror image of Top16: the cutoff is an upper bound, so only values
+//! smaller than it will be considered, and the iterator returns the values in
+//! ascending order.  That is just a TopFew with the Min order, whose comparisons
+//! are reversed at compile time, so a BottomFew is exactly as fast as a Top16.
+
+use crate::ixs::{Cap, Capacity};
+use crate::order::Min;
+use crate::top16::TopFew;
+
+/// The bottom 16 u32 values.
+pub type BottomFew = TopFew<u32, 16, Min>;
+
+impl<T: Ord + Copy, const N: usize> TopFew<T, N, Min>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns the smallest element in the bottom 16, which is what max() returns
+    /// for the Min order, since it ranks highest.
+    #[inline]
+    pub fn min(&self) -> Option<T> {
+        self.max()
+    }
+}
@@ -0,0 +1,75 @@
+//! TopFewBy keeps track of the 16 items with the top keys seen so far,
+//! where the key of an item is computed by a function you provide.
+//!
+//! The keys are kept in a TopFew, so the search is just as fast as for a Top16,
+//! and the items themselves are kept in an array parallel to its elements.
+//! Since the items never take part in the search, they need not be Copy,
+//! or even Clone; each slot simply holds the item whose key is in it, if any.
+//!
+//! As with a Top16, only items whose keys are larger than the cutoff are considered,
+//! and an item whose key only ties the smallest retained key does not get in.
+
+use crate::TopFew;
+
+/// The 16 items with the top keys seen.
+#[derive(Clone, Debug)]
+pub struct TopFewBy<T, K, F = fn(&T) -> K> {
+    // The keys of the top items.
+    top: TopFew<K>,
+    // The item for each of the top's slots.
+    items: [Option<T>; 16],
+    // Computes the key of an item.
+    key_fn: F,
+}
+
+impl<T, K: Ord + Copy, F: Fn(&T) -> K> TopFewBy<T, K, F> {
+    /// Returns a new instance of TopFewBy that orders items by the keys
+    /// that key_fn returns for them.
+    /// Only items with keys larger than the cutoff will be considered.
+    pub fn new(cutoff: K, key_fn: F) -> Self {
+        Self {
+            top: TopFew::new(cutoff),
+            items: [const { None }; 16],
+            key_fn,
+        }
+    }
+
+    /// Returns the current cutoff key.
+    #[inline]
+    pub fn cutoff(&self) -> K {
+        self.top.cutoff()
+    }
+
+    /// Considers a new item to see if its key is one of the top 16.
+    /// If so, it is added, and the item with the smallest key is dropped.
+    /// Returns true if the item was added.
+    pub fn see(&mut self, item: T) -> bool {
+        if let Some(slot) = self.top.see_slot((self.key_fn)(&item)) {
+            self.items[slot] = Some(item);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the item with the largest key, if any.
+    pub fn max(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    /// Returns an Iterator over the top items, in descending order of key.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        // Every retained key's slot holds the item it came from.
+        self.top
+            .slots()
+            .filter_map(|(slot, _)| self.items[slot].as_ref())
+    }
+
+    /// Returns an Iterator over the (key, item) pairs of the top items,
+    /// in descending order of key.
+    pub fn iter_keyed(&self) -> impl DoubleEndedIterator<Item = (K, &T)> + '_ {
+        self.top
+            .slots()
+            .filter_map(|(slot, k)| Some((k, self.items[slot].as_ref()?)))
+    }
+}
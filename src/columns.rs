@@ -0,0 +1,30 @@
+//! Helpers for data laid out in rows and columns, available with the `alloc` feature.
+//!
+//! The data is read in the order it is stored in, one row after another,
+//! so the reads are sequential, and the Top16s for the columns sit next to each other
+//! in a Vec.  As long as there are not so many columns that their Top16s fall
+//! out of the cache, that is about as cache-friendly as it gets.
+
+use alloc::vec::Vec;
+
+use crate::Top16;
+
+/// Returns a Top16 for each column of data, which is treated as row-major with
+/// the specified number of columns, so data[r * cols + c] goes to column c's Top16.
+/// Only values larger than the cutoff will be considered.
+/// If the length of data is not a multiple of cols, the last row is partial,
+/// and its values go to the first columns.
+///
+/// # Panics
+///
+/// Panics if cols is 0.
+pub fn top16_columns(data: &[u32], cols: usize, cutoff: u32) -> Vec<Top16> {
+    assert!(cols > 0, "there must be at least one column");
+    let mut tops = alloc::vec![Top16::new(cutoff); cols];
+    for row in data.chunks(cols) {
+        for (top, &v) in tops.iter_mut().zip(row) {
+            top.see(v);
+        }
+    }
+    tops
+}
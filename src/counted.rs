@@ -0,0 +1,76 @@
+//! TopFewCounted keeps track of the top 16 distinct values seen so far,
+//! along with how many times each of them has been seen.
+//!
+//! A Top16 may include a value several times if it is seen several times.
+//! That is the (value, count) idea from the Top16 docs, but with the counting
+//! done for you: when a retained value is seen again its count goes up,
+//! rather than another copy of it pushing out the smallest value.
+//!
+//! Counts only cover the time a value has been retained.  If a value is evicted
+//! and later seen again, its count starts over at 1.  The total of the counts is
+//! kept up to date as values come and go, with an evicted value's count discarded.
+
+use crate::Top16;
+
+/// The top 16 distinct values seen, with a count for each.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewCounted {
+    // The distinct values.
+    top: Top16,
+    // The count for each of the top's slots, or 0 if it is unfilled.
+    counts: [u32; 16],
+    // The sum of the counts.
+    total: u64,
+}
+
+impl TopFewCounted {
+    /// Returns a new instance of TopFewCounted.
+    /// Only values larger than the cutoff will be considered.
+    pub fn new(cutoff: u32) -> Self {
+        Self {
+            top: Top16::new(cutoff),
+            counts: [0; 16],
+            total: 0,
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> u32 {
+        self.top.cutoff()
+    }
+
+    /// Returns the sum of the counts of the retained values, i.e. how many of the
+    /// values seen are accounted for by the top distinct values.
+    #[inline]
+    pub fn total_count(&self) -> u64 {
+        self.total
+    }
+
+    /// Considers a new value.  If it is already retained, its count is incremented;
+    /// otherwise, if it is one of the top 16 distinct values, it is added with a count of 1.
+    pub fn see(&mut self, value: u32) {
+        // Anything below the threshold can be neither retained nor admitted.
+        if value < self.top.threshold() {
+            return;
+        }
+        // Finding an existing entry is a scan of the 16 elements.
+        if let Some(slot) = self.top.slot_of(value) {
+            if let Some(count) = self.counts[slot].checked_add(1) {
+                self.counts[slot] = count;
+                self.total += 1;
+            }
+        } else if let Some(slot) = self.top.see_slot(value) {
+            // The evicted value's count, if any, goes with it.
+            self.total -= u64::from(self.counts[slot]);
+            self.total += 1;
+            self.counts[slot] = 1;
+        }
+    }
+
+    /// Returns an Iterator over the (value, count) pairs of the top distinct values,
+    /// in descending order of value.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u32, u32)> + '_ {
+        self.top.slots().map(|(slot, v)| (v, self.counts[slot]))
+    }
+}
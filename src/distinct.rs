@@ -0,0 +1,184 @@
+//! TopFewDistinct keeps track of the top 16 distinct values seen so far.
+//!
+//! A TopFew may include a value several times if it is seen several times.
+//! This wraps one and rejects a value that is equal to one it already retains,
+//! so nothing is evicted for it, and rank() returns 0 for it.
+//! Unlike TopFewCounted, it does not keep track of how many times each value was seen,
+//! and it works for any values and order a TopFew does.
+//!
+//! The duplicate check is a scan of the retained values, but it only happens
+//! for values that get past the threshold, so rejecting a value is still
+//! just a compare and branch.
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+use crate::ixs::{Cap, Capacity};
+use crate::order::{Max, Order};
+use crate::top16::{Iter, TopFew};
+
+/// The top 16 distinct values seen.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewDistinct<T = u32, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
+    // The values, none of which is retained twice.
+    top: TopFew<T, N, O>,
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> TopFewDistinct<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns a new instance of TopFewDistinct.
+    /// Only values larger than the cutoff will be considered.
+    pub fn new(cutoff: T) -> Self {
+        Self {
+            top: TopFew::new(cutoff),
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> T {
+        self.top.cutoff()
+    }
+
+    /// Returns the current threshold, as for TopFew::threshold().
+    /// A value must be larger than this to be admitted, and not already retained.
+    #[inline]
+    pub fn threshold(&self) -> T {
+        self.top.threshold()
+    }
+
+    /// Returns the number of retained values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.top.len()
+    }
+
+    /// Returns true if no values are retained.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.top.is_empty()
+    }
+
+    /// Returns the largest retained value.
+    #[inline]
+    pub fn max(&self) -> Option<T> {
+        self.top.max()
+    }
+
+    /// Returns true if the value is one of the retained top values.
+    #[inline]
+    pub fn contains(&self, value: T) -> bool {
+        self.top.contains(value)
+    }
+
+    /// Returns true if seeing the value now would add it,
+    /// i.e. the threshold lets it in and it is not already retained.
+    #[inline]
+    pub fn would_admit(&self, value: T) -> bool {
+        self.top.would_admit(value) && !self.top.contains(value)
+    }
+
+    /// Considers a new value, as TopFew::rank() does, and returns its position,
+    /// or 0 if it was not admitted, which includes it already being retained.
+    #[inline]
+    pub fn rank(&mut self, value: T) -> usize {
+        // The scan only happens for values that get past the threshold.
+        if self.top.would_admit(value) && self.top.contains(value) {
+            self.top.note_seen(value);
+            return 0;
+        }
+        self.top.rank(value)
+    }
+
+    /// Considers a new value, and adds it if it is one of the top 16 distinct values.
+    #[inline]
+    pub fn see(&mut self, value: T) {
+        self.rank(value);
+    }
+
+    /// Considers each of the values in turn, as see() does, and returns
+    /// the number of them that were admitted.
+    pub fn see_all<I: IntoIterator<Item = T>>(&mut self, values: I) -> usize {
+        values
+            .into_iter()
+            .map(|v| usize::from(self.rank(v) != 0))
+            .sum()
+    }
+
+    /// Considers each of the TopFew's retained values, as see() does,
+    /// so that this one ends up with the top 16 distinct values that both have seen.
+    /// For another TopFewDistinct, pass its as_top_few().
+    pub fn merge(&mut self, other: &TopFew<T, N, O>) {
+        self.top.note_max_ever_of(other);
+        // The other's values come in descending order, so once one of them
+        // is not let in by the threshold, none of the rest will be either.
+        for value in other.iter() {
+            if !self.top.would_admit(value) {
+                break;
+            }
+            self.see(value);
+        }
+    }
+
+    /// Returns an Iterator over the retained values, in descending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, N, O> {
+        self.top.iter()
+    }
+
+    /// Returns the TopFew that holds the values, e.g. for its other read-only methods.
+    #[inline]
+    pub fn as_top_few(&self) -> &TopFew<T, N, O> {
+        &self.top
+    }
+
+    /// Checks the invariants of the TopFew, as TopFew::check_invariants() does,
+    /// and also that no value is retained twice.
+    #[cfg(feature = "alloc")]
+    pub fn check_invariants(&self) -> Result<(), String>
+    where
+        T: core::fmt::Debug,
+    {
+        self.top.check_invariants()?;
+        let mut values = self.top.iter();
+        let mut prev = values.next();
+        for v in values {
+            if prev == Some(v) {
+                return Err(format!("element {v:?} is retained twice"));
+            }
+            prev = Some(v);
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize, O: Order<Direction = Max>> TopFewDistinct<u32, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns the smallest value that would be admitted if it were seen now,
+    /// as TopFew::next_admission_value() does, but skipping over the values
+    /// that are already retained, since those would be rejected.
+    /// Returns None if nothing more can be admitted, e.g. if everything
+    /// from the threshold up to u32::MAX is already retained.
+    pub fn next_admission_value(&self) -> Option<u32> {
+        let mut next = self.top.next_admission_value();
+        if !self.top.would_admit(next) {
+            return None;
+        }
+        // The retained values from there up, in ascending order.
+        let start = next;
+        for v in self.top.iter().rev().skip_while(|&v| v < start) {
+            if v != next {
+                break;
+            }
+            next = next.checked_add(1)?;
+        }
+        Some(next)
+    }
+}
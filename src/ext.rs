@@ -0,0 +1,38 @@
+//! TopFewExt adds TopFew-related methods to iterators.
+//!
+//! Bring it into scope with `use top_few::TopFewExt;`, and then e.g.
+//! `values.into_iter().inspect_top16(&mut top)` feeds the values to a Top16
+//! as they go by on their way to whatever consumes the iterator.
+
+use crate::ixs::{Cap, Capacity};
+use crate::order::Order;
+use crate::top16::TopFew;
+
+/// Extension methods for iterators over the values a TopFew holds.
+pub trait TopFewExt: Iterator {
+    /// Returns an iterator that shows each value to the TopFew, as see() does,
+    /// and then yields it unchanged, like inspect().  Once the iterator has been
+    /// consumed, the TopFew has the top 16 of the values that went through it.
+    ///
+    /// ```
+    /// use top_few::{Top16, TopFewExt};
+    ///
+    /// let mut top = Top16::new(0);
+    /// let total: u32 = (1..=100).inspect_top16(&mut top).sum();
+    /// assert_eq!(total, 5050);
+    /// assert_eq!(top.max(), Some(100));
+    /// ```
+    fn inspect_top16<'a, const N: usize, O: Order>(
+        self,
+        top: &'a mut TopFew<Self::Item, N, O>,
+    ) -> impl Iterator<Item = Self::Item> + 'a
+    where
+        Self: Sized + 'a,
+        Self::Item: Ord + Copy,
+        Cap<N>: Capacity,
+    {
+        self.inspect(move |&value| top.see(value))
+    }
+}
+
+impl<I: Iterator> TopFewExt for I {}
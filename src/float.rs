@@ -0,0 +1,88 @@
+//! TopFewF32 keeps track of the top 16 f32 values seen so far.
+//!
+//! Since f32 is not Ord, the values are ordered by f32::total_cmp.  We get that
+//! order for free by mapping each f32 to a u32 key whose unsigned order is the
+//! same as total_cmp's, and keeping the keys in a Top16: flip the sign bit of
+//! positive values, and flip all the bits of negative ones.  So a TopFewF32
+//! is exactly as fast as a Top16.
+//!
+//! The total_cmp order is
+//! -NaN < -infinity < ... < -0.0 < +0.0 < ... < +infinity < +NaN,
+//! so a positive NaN sorts highest and will be retained like any other large value,
+//! while a negative NaN sorts lowest and will not be retained unless the cutoff is
+//! also a negative NaN.  If you don't want NaNs, filter them out before seeing them.
+//! Note that -0.0 is less than +0.0, so with a cutoff of 0.0 a -0.0 is rejected,
+//! and with a cutoff of -0.0 a +0.0 is retained.
+
+use crate::Top16;
+
+/// The top 16 f32 values seen, in f32::total_cmp order.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewF32 {
+    // The keys of the top values.
+    top: Top16,
+}
+
+// Returns the key for an f32, ordered the same way total_cmp orders the f32s.
+#[inline]
+fn key(v: f32) -> u32 {
+    let bits = v.to_bits();
+    // All ones for negative values, just the sign bit for positive ones.
+    let mask = ((bits as i32 >> 31) as u32) | 0x8000_0000;
+    bits ^ mask
+}
+
+// Returns the f32 for a key; the inverse of key().
+#[inline]
+fn value(k: u32) -> f32 {
+    let mask = (((!k) as i32 >> 31) as u32) | 0x8000_0000;
+    f32::from_bits(k ^ mask)
+}
+
+impl TopFewF32 {
+    /// Returns a new instance of TopFewF32.
+    /// Only values larger than the cutoff (per f32::total_cmp) will be considered.
+    pub fn new(cutoff: f32) -> Self {
+        Self {
+            top: Top16::new(key(cutoff)),
+        }
+    }
+
+    /// Changes the cutoff value to the specified new value.
+    /// Note that this removes values that are smaller than the new cutoff.
+    pub fn set_cutoff(&mut self, new_cutoff: f32) {
+        self.top.set_cutoff(key(new_cutoff));
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> f32 {
+        value(self.top.cutoff())
+    }
+
+    /// Returns the largest element in the top 16.
+    #[inline]
+    pub fn max(&self) -> Option<f32> {
+        self.top.max().map(value)
+    }
+
+    /// Considers a new value to see if is one of the top 16, and returns its position
+    /// as described for Top16::rank().
+    #[inline]
+    pub fn rank(&mut self, v: f32) -> usize {
+        self.top.rank(key(v))
+    }
+
+    /// Considers a new value to see if is one of the top 16.
+    /// If so, it is added to the list.
+    #[inline]
+    pub fn see(&mut self, v: f32) {
+        self.top.see(key(v));
+    }
+
+    /// Returns an Iterator over the top 16 elements (or less if there are less), in descending order.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = f32> + ExactSizeIterator + '_ {
+        self.top.iter().map(value)
+    }
+}
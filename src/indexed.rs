@@ -0,0 +1,67 @@
+//! TopFewIndexed keeps track of the top 16 values seen so far, along with
+//! where in the stream each of them was seen.
+//!
+//! Each retained value is stamped with its 0-based position in the stream,
+//! counting every value seen, including the ones that were not admitted.
+//! The positions are kept in an array parallel to the Top16's elements,
+//! so when a value takes over the smallest value's slot, its position does too.
+
+use crate::Top16;
+
+/// The top 16 values seen, with the stream position of each.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewIndexed {
+    // The top values.
+    top: Top16,
+    // The stream position of the value in each of the top's slots.
+    positions: [u64; 16],
+    // The stream position of the next value seen.
+    next_position: u64,
+}
+
+impl TopFewIndexed {
+    /// Returns a new instance of TopFewIndexed.
+    /// Only values larger than the cutoff will be considered.
+    pub fn new(cutoff: u32) -> Self {
+        Self {
+            top: Top16::new(cutoff),
+            positions: [0; 16],
+            next_position: 0,
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> u32 {
+        self.top.cutoff()
+    }
+
+    /// Returns the number of values seen so far, which is the position of the next one.
+    #[inline]
+    pub fn seen(&self) -> u64 {
+        self.next_position
+    }
+
+    /// Considers a new value to see if is one of the top 16.
+    /// If so, it is added to the list along with its position in the stream.
+    pub fn see(&mut self, value: u32) {
+        let position = self.next_position;
+        self.next_position += 1;
+        if let Some(slot) = self.top.see_slot(value) {
+            self.positions[slot] = position;
+        }
+    }
+
+    /// Returns an Iterator over the top 16 elements (or less if there are less),
+    /// in descending order.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u32> + '_ {
+        self.top.iter()
+    }
+
+    /// Returns an Iterator over the (value, stream position) pairs of the top values,
+    /// in descending order of value.
+    pub fn iter_with_positions(&self) -> impl DoubleEndedIterator<Item = (u32, u64)> + '_ {
+        self.top.slots().map(|(slot, v)| (v, self.positions[slot]))
+    }
+}
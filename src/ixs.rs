@@ -0,0 +1,202 @@
+//! The sorted indices of a TopFew, packed a few bits apiece into an integer.
+//!
+//! A TopFew holding N elements keeps the indices of its elements in an `IndexStore`,
+//! which it gets from the `Capacity` implementation for `Cap<N>`:
+//! up to 8 elements use a u32 with four bits per index,
+//! up to 16 use a u64 with four bits per index, which is what a Top16 uses,
+//! and up to 25 use a u128 with five bits per index.
+//! Every store is searched and updated with the same handful of shifts,
+//! so all of the capacities share one implementation.
+//!
+//! On 32-bit targets every u64 shift turns into several instructions,
+//! so there the 16-element store is split into two usize words instead:
+//! the lower eight indices in `lo` and the upper eight in `hi`.
+//! Either way, an index is addressed by its bit position ("shift") in the full
+//! sequence, so the rest of the code does not care which store it gets.
+
+use core::fmt::Debug;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Packed indices, with the index at each position taking `IX_BITS` bits.
+/// This is implemented for the crate's own stores only.
+pub trait IndexStore: Copy + Debug + sealed::Sealed {
+    /// The number of bits that hold an index.
+    const IX_BITS: u32;
+    /// The number of indices the store has room for.
+    const SLOTS: usize;
+    /// Each element's index at its own position, i.e. elements already in order.
+    const IDENTITY: Self;
+
+    /// Returns the index at the specified shift.
+    fn get(self, shift: u32) -> usize;
+
+    /// Moves the index of the smallest element (at shift 0) to the specified shift,
+    /// moving the indices in between down to fill the gap.
+    fn promote_min(&mut self, shift: u32);
+
+    /// Returns the indices as a single u128, e.g. for debugging.
+    fn to_u128(self) -> u128;
+
+    /// Returns the indices packed in the specified u128.
+    fn from_u128(ixs: u128) -> Self;
+}
+
+/// Selects the `IndexStore` for a TopFew with N elements; see `Capacity`.
+pub struct Cap<const N: usize>;
+
+/// Implemented for `Cap<N>` for each N from 1 to 25, the capacities that a TopFew supports.
+pub trait Capacity {
+    /// The smallest store with room for N indices.
+    type Store: IndexStore;
+}
+
+macro_rules! store {
+    ($t:ty, $ix_bits:expr) => {
+        impl sealed::Sealed for $t {}
+
+        impl IndexStore for $t {
+            const IX_BITS: u32 = $ix_bits;
+            const SLOTS: usize = (<$t>::BITS / $ix_bits) as usize;
+            const IDENTITY: Self = {
+                let mut ixs = 0;
+                let mut ix = 0;
+                while ix < Self::SLOTS as $t {
+                    ixs |= ix << (ix as u32 * $ix_bits);
+                    ix += 1;
+                }
+                ixs
+            };
+
+            #[inline]
+            fn get(self, shift: u32) -> usize {
+                ((self >> shift) & ((1 << $ix_bits) - 1)) as usize
+            }
+
+            #[inline]
+            fn promote_min(&mut self, shift: u32) {
+                // E.g. for a u64, if shift = 48 and the indices are 0xFEDCBA9876543210,
+                // upper = 0xFEDCBA9876543210 >> 52       = 0x0000000000000FED
+                // lower = 0xFEDCBA9876543210 << 12 >> 16 = 0x0000CBA987654321
+                // indices                                = 0xFED0CBA987654321
+                //                           promoted index ^
+                // Note that we have to include the index at shift in lower,
+                // and we have to get rid of the smallest element's index,
+                // which is in the least significant bits.
+                const BITS: u32 = <$t>::BITS;
+                let lower = (*self << (BITS - $ix_bits - shift)).unbounded_shr(BITS - shift);
+                let upper = self.unbounded_shr(shift + $ix_bits);
+                let min_ix = *self & ((1 << $ix_bits) - 1);
+                *self = (((upper << $ix_bits) | min_ix) << shift) | lower;
+            }
+
+            #[inline]
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            #[inline]
+            fn from_u128(ixs: u128) -> Self {
+                ixs as $t
+            }
+        }
+    };
+}
+
+store!(u32, 4);
+store!(u64, 4);
+store!(u128, 5);
+
+macro_rules! capacity {
+    ($store:ty: $($n:literal)*) => {
+        $(impl Capacity for Cap<$n> {
+            type Store = $store;
+        })*
+    };
+}
+
+capacity!(u32: 1 2 3 4 5 6 7 8);
+capacity!(SortedIxs: 9 10 11 12 13 14 15 16);
+capacity!(u128: 17 18 19 20 21 22 23 24 25);
+
+/// The store for 9 to 16 indices: a u64, or on 32-bit targets a `SplitIxs`.
+#[cfg(not(target_pointer_width = "32"))]
+pub type SortedIxs = u64;
+
+/// The store for 9 to 16 indices: a u64, or on 32-bit targets a `SplitIxs`.
+#[cfg(target_pointer_width = "32")]
+pub type SortedIxs = SplitIxs;
+
+/// Sixteen 4-bit indices split across two usize words, for 32-bit targets.
+#[cfg(target_pointer_width = "32")]
+#[derive(Clone, Copy, Debug)]
+pub struct SplitIxs {
+    // The indices at shifts 0 through 28.
+    lo: usize,
+    // The indices at shifts 32 through 60.
+    hi: usize,
+}
+
+#[cfg(target_pointer_width = "32")]
+const WORD_BITS: u32 = usize::BITS;
+
+#[cfg(target_pointer_width = "32")]
+impl sealed::Sealed for SplitIxs {}
+
+#[cfg(target_pointer_width = "32")]
+impl IndexStore for SplitIxs {
+    const IX_BITS: u32 = 4;
+    const SLOTS: usize = 16;
+    const IDENTITY: Self = Self {
+        lo: 0x76543210,
+        hi: 0xFEDCBA98,
+    };
+
+    #[inline]
+    fn get(self, shift: u32) -> usize {
+        let word = if shift < WORD_BITS { self.lo } else { self.hi };
+        (word >> (shift % WORD_BITS)) & 0xF
+    }
+
+    #[inline]
+    fn promote_min(&mut self, shift: u32) {
+        let min_ix = self.lo & 0xF;
+        if shift < WORD_BITS {
+            // Only lo changes.
+            self.lo = promote_within(self.lo, min_ix, shift);
+        } else {
+            // All of lo moves down, with the lowest index in hi crossing over
+            // into its top position, and then hi does the same thing lo does above.
+            self.lo = (self.lo >> Self::IX_BITS) | (self.hi << (WORD_BITS - Self::IX_BITS));
+            self.hi = promote_within(self.hi, min_ix, shift - WORD_BITS);
+        }
+    }
+
+    #[inline]
+    fn to_u128(self) -> u128 {
+        ((self.hi as u128) << WORD_BITS) | self.lo as u128
+    }
+
+    #[inline]
+    fn from_u128(ixs: u128) -> Self {
+        Self {
+            lo: ixs as usize,
+            hi: (ixs >> WORD_BITS) as usize,
+        }
+    }
+}
+
+// Drops the lowest index in word, moves the indices up through the specified shift
+// down into the gap, and puts ix at the specified shift.  The indices above it
+// are left alone.
+#[cfg(target_pointer_width = "32")]
+#[inline]
+fn promote_within(word: usize, ix: usize, shift: u32) -> usize {
+    const IX_BITS: u32 = SplitIxs::IX_BITS;
+    let below = |shift: u32| !usize::MAX.unbounded_shl(shift); // mask for bits below shift
+    let upper = word & !below(shift + IX_BITS);
+    let lower = (word >> IX_BITS) & below(shift);
+    upper | (ix << shift) | lower
+}
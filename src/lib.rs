@@ -14,10 +14,14 @@
 //! will be considered.  So, for example, if you are using u32 values
 //! and specify 0 as the cutoff, then 0s will never be included in the result,
 //! even if all the values seen were 0.
-//! If you really need to include 0s in the result,
-//! you can use Option<u32> values with None as the cutoff value.
-//! Or you could use (u32, u32) values, where the second u32 is a counter,
-//! with (0,0) as the cutoff value.
+//! If you really need to include 0s in the result, you can use a TopFewUnbounded,
+//! or `Option<u32>` values with None as the cutoff value.
+//! Or you could use `(u32, u32)` values, where the second u32 is a counter,
+//! with (0,0) as the cutoff.  Or you could use i32 values with -1 as the cutoff.
+//! Top16 is the u32 version of TopFew, which works with any type that is Ord and Copy,
+//! so those are just `TopFew<Option<u32>>`, `TopFew<(u32, u32)>` and `TopFew<i32>`.
+//! The values are compared using their own ordering, so e.g. -5 is below 3 in a `TopFew<i32>`.
+//! To keep the smallest values instead, use `TopFew<T, N, Min>`; see the order module.
 //!
 //! Having a cutoff value helps performance in a few ways.
 //! We initialize the list to the cutoff value, so we always have 16 values,
@@ -31,6 +35,9 @@
 //! If a given value is seen multiple times, it can be included multiple times.
 //! New values do not replace existing values; the oldest instances are retained,
 //! and are reported first by iterators.
+//! If you would rather have newer values win ties, use the NewestWins order,
+//! and if you only want distinct values, use TopFewDistinct.
+//! To also have an upper bound on the values, use TopFewBand.
 //!
 //! Top16 is designed for streaming use cases, where you show it values as they
 //! come in, and it keeps track of the top 16 values seen so far.
@@ -43,25 +50,65 @@
 //! get them in ascending order.  Note that you will get less than 16 values
 //! if it has not seen 16 values larger than the cutoff.
 
+#![no_std]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 // TODO:
-// - .max() should return an Option, right?
-// - Extension method for Iterator, e.g. nums.iter().top16(cutoff).
-// - But take IntoIterator.
-// - Criterion benchmarks.
 // - Try a.cmp(b); remember that 0 (equals) means that we do not know whether older or newer is kept.
-// - Use usize instead of u64 for sorted_ixs.
-// - #[cfg(target_pointer_width = "64")]
-// - 32-bit version using two usizes.
-// - generic T that is comparable, e.g. T: Ord + Copy
-// - try Option<u32> with None as the cutoff value
 // - faster than .take(): top(5) and bottom(5) methods.
 // - doc tests
 // - README.md and docs
-// - Top8
 // - API Guidelines Checklist
-// - Check the assembly language.  Index unchecked?  Binary search?  max() doesn't mask?
 // Godbolt: https://godbolt.org/z/7er6vYjax
 
+pub mod band;
+pub mod bottom;
+pub mod by;
+#[cfg(feature = "alloc")]
+pub mod columns;
+pub mod counted;
+pub mod distinct;
+pub mod ext;
+pub mod float;
+pub mod indexed;
+pub mod ixs;
+pub mod order;
+#[cfg(feature = "rayon")]
+pub mod par;
+#[cfg(feature = "alloc")]
+pub mod pool;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod top1;
 pub mod top16;
+pub mod topk;
+pub mod unbounded;
+pub mod weighted;
+pub mod window;
 
-pub use top16::{Iter, Top16};
+pub use band::TopFewBand;
+pub use bottom::BottomFew;
+pub use by::TopFewBy;
+pub use counted::TopFewCounted;
+pub use distinct::TopFewDistinct;
+pub use ext::TopFewExt;
+pub use float::TopFewF32;
+pub use indexed::TopFewIndexed;
+pub use ixs::{Cap, Capacity};
+pub use order::{Max, Min, NewestWins, Order};
+#[cfg(feature = "alloc")]
+pub use pool::TopFewPool;
+pub use top1::Top1;
+pub use top16::{
+    InsertResult, IntoIter, Iter, ParseError, ScopeGuard, SeeOutcome, Top8, Top16, TopFew, TopN,
+    UndoToken,
+};
+pub use topk::TopK;
+pub use unbounded::{MinValue, TopFewUnbounded};
+pub use weighted::TopFewWeighted;
+pub use window::TopFewWindow;
@@ -0,0 +1,126 @@
+//! The order that a TopFew ranks its values by, chosen at compile time.
+//!
+//! A `TopFew<T, N, Max>`, which is what you get by default, keeps the largest values,
+//! and a `TopFew<T, N, Min>` keeps the smallest.  The order is a zero-sized marker type,
+//! so each comparison in the search is resolved when the code is monomorphized,
+//! and neither order has a flag to check.
+//!
+//! The order also decides what happens to ties.  Normally the oldest instances of
+//! equal values are retained and reported first, and a value that only ties the smallest
+//! retained value does not get in.  With NewestWins, e.g. `TopFew<T, N, NewestWins>`
+//! or `TopFew<T, N, NewestWins<Min>>`, a new value goes above the values that are equal
+//! to it, evicting the oldest instance when it ties the smallest.  That too is decided
+//! at compile time, so the default TopFew does not check for it.
+//!
+//! The documentation for TopFew talks about larger and smaller values,
+//! which for a Min TopFew mean the values that rank higher and lower,
+//! i.e. the smaller and larger ones.  So for example its cutoff is an upper bound,
+//! max() returns its smallest value, and its iterator returns values in ascending order.
+
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Which values rank higher in a TopFew.
+/// This is implemented for Max and Min only.
+pub trait Order: Copy + Debug + Default + Eq + Hash + sealed::Sealed {
+    /// Max or Min, i.e. the order without the tie rule.
+    type Direction: Order;
+
+    /// Whether a new value that is equal to retained ones goes above them.
+    const NEWEST_WINS: bool;
+
+    /// Returns true if a ranks above b.
+    fn gt<T: Ord>(a: &T, b: &T) -> bool;
+
+    /// Returns true if a ranks above b or equal to it.
+    fn ge<T: Ord>(a: &T, b: &T) -> bool;
+
+    /// Returns Greater if a ranks above b, Less if it ranks below, and otherwise Equal.
+    fn cmp<T: Ord>(a: &T, b: &T) -> Ordering;
+}
+
+/// Ranks larger values higher, so a TopFew keeps the largest values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Max;
+
+/// Ranks smaller values higher, so a TopFew keeps the smallest values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Min;
+
+/// Ranks values as O does (Max unless you say otherwise), but newer values win ties:
+/// a value equal to the smallest retained value is admitted, evicting the oldest
+/// instance of it, and equal values are reported newest first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NewestWins<O = Max>(PhantomData<O>);
+
+impl sealed::Sealed for Max {}
+
+impl Order for Max {
+    type Direction = Max;
+    const NEWEST_WINS: bool = false;
+
+    #[inline(always)]
+    fn gt<T: Ord>(a: &T, b: &T) -> bool {
+        a > b
+    }
+
+    #[inline(always)]
+    fn ge<T: Ord>(a: &T, b: &T) -> bool {
+        a >= b
+    }
+
+    #[inline(always)]
+    fn cmp<T: Ord>(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl sealed::Sealed for Min {}
+
+impl Order for Min {
+    type Direction = Min;
+    const NEWEST_WINS: bool = false;
+
+    #[inline(always)]
+    fn gt<T: Ord>(a: &T, b: &T) -> bool {
+        a < b
+    }
+
+    #[inline(always)]
+    fn ge<T: Ord>(a: &T, b: &T) -> bool {
+        a <= b
+    }
+
+    #[inline(always)]
+    fn cmp<T: Ord>(a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<O: Order<Direction = O>> sealed::Sealed for NewestWins<O> {}
+
+impl<O: Order<Direction = O>> Order for NewestWins<O> {
+    type Direction = O;
+    const NEWEST_WINS: bool = true;
+
+    #[inline(always)]
+    fn gt<T: Ord>(a: &T, b: &T) -> bool {
+        O::gt(a, b)
+    }
+
+    #[inline(always)]
+    fn ge<T: Ord>(a: &T, b: &T) -> bool {
+        O::ge(a, b)
+    }
+
+    #[inline(always)]
+    fn cmp<T: Ord>(a: &T, b: &T) -> Ordering {
+        O::cmp(a, b)
+    }
+}
@@ -0,0 +1,25 @@
+//! Parallel helpers for rayon users, available with the `rayon` feature.
+//!
+//! Each rayon job folds its share of the values into its own Top16,
+//! and then the Top16s are reduced with merge().  Because merging is associative,
+//! it does not matter how rayon splits up the work; the result is the same
+//! as seeing all of the values in order.
+
+use rayon::prelude::*;
+
+use crate::Top16;
+
+/// Returns a Top16 holding the top 16 values of the slice, as if it had seen them
+/// in order, computed in parallel.  Only values larger than the cutoff will be considered.
+pub fn par_top16(slice: &[u32], cutoff: u32) -> Top16 {
+    slice
+        .par_iter()
+        .fold(
+            || Top16::new(cutoff),
+            |mut top, &v| {
+                top.see(v);
+                top
+            },
+        )
+        .reduce(|| Top16::new(cutoff), |a, b| a.merged(&b))
+}
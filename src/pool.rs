@@ -0,0 +1,86 @@
+//! TopFewPool hands out Top16s for short-lived accumulations and takes them back
+//! when they are done, so that a program that uses thousands of them a second
+//! does not keep growing and shrinking collections of them.
+//!
+//! A Top16 is Copy and does not allocate, so the pool is just a Vec of them
+//! and a list of the free ones.  Acquiring one reinitializes it with the new cutoff,
+//! and you refer to it by the index that acquire() returns until you release it.
+
+use alloc::vec::Vec;
+
+use crate::Top16;
+
+/// A reusable collection of Top16s.
+#[derive(Clone, Debug, Default)]
+pub struct TopFewPool {
+    // All of the Top16s, whether in use or not.
+    tops: Vec<Top16>,
+    // The indices of the ones that are not in use.
+    free: Vec<usize>,
+    // Whether each of them is in use, so that releasing one twice can be caught.
+    acquired: Vec<bool>,
+}
+
+impl TopFewPool {
+    /// Returns a new, empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new pool with room for the specified number of Top16s
+    /// before it needs to allocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tops: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            acquired: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the index of a Top16 that is as if it were newly created with the cutoff,
+    /// along with the Top16 itself.  It reuses a released one if there is one.
+    pub fn acquire(&mut self, cutoff: u32) -> (usize, &mut Top16) {
+        let ix = match self.free.pop() {
+            Some(ix) => {
+                self.tops[ix].reinit(cutoff);
+                ix
+            }
+            None => {
+                self.tops.push(Top16::new(cutoff));
+                self.acquired.push(false);
+                self.tops.len() - 1
+            }
+        };
+        self.acquired[ix] = true;
+        (ix, &mut self.tops[ix])
+    }
+
+    /// Returns the Top16 with the specified index, from acquire().
+    #[inline]
+    pub fn get(&self, ix: usize) -> &Top16 {
+        &self.tops[ix]
+    }
+
+    /// Returns the Top16 with the specified index, from acquire(), for seeing values.
+    #[inline]
+    pub fn get_mut(&mut self, ix: usize) -> &mut Top16 {
+        &mut self.tops[ix]
+    }
+
+    /// Gives back the Top16 with the specified index, so that acquire() can reuse it.
+    /// The index must not be used again until acquire() returns it.
+    /// It panics if the Top16 has already been released, since otherwise two later
+    /// acquire() calls would both return it.
+    pub fn release(&mut self, ix: usize) {
+        assert!(ix < self.tops.len(), "index {ix} was never acquired");
+        assert!(self.acquired[ix], "index {ix} released twice");
+        self.acquired[ix] = false;
+        self.free.push(ix);
+    }
+
+    /// Returns the number of Top16s that are currently acquired.
+    #[inline]
+    pub fn in_use(&self) -> usize {
+        self.tops.len() - self.free.len()
+    }
+}
@@ -0,0 +1,23 @@
+//! Proptest strategies for testing code that uses the crate's types,
+//! available with the `proptest` feature.
+//!
+//! The instances are built by feeding a random stream of values to a new instance
+//! with a random cutoff, so they are always in a state that the public API can produce.
+
+use ::proptest::collection::vec;
+use ::proptest::prelude::*;
+
+use crate::Top16;
+
+/// Returns a Strategy that generates arbitrary Top16s.
+/// Small cutoffs and small values are favored, so that many of the instances
+/// are full and hold duplicate values.
+pub fn any_top16() -> impl Strategy<Value = Top16> {
+    let cutoff = prop_oneof![Just(0u32), 0..32u32, any::<u32>()];
+    let value = prop_oneof![0..32u32, any::<u32>()];
+    (cutoff, vec(value, 0..64)).prop_map(|(cutoff, values)| {
+        let mut top = Top16::new(cutoff);
+        top.see_all(values);
+        top
+    })
+}
@@ -0,0 +1,69 @@
+//! Top1 keeps track of just the largest value seen so far.
+//!
+//! That is only a max with a cutoff, but it has the same see(), max(), iter() and
+//! set_cutoff() as a Top16, and it implements TopK like the TopFews do,
+//! so generic code can use a Top1 wherever only the largest value is needed.
+
+use core::option;
+
+/// The largest value seen, if it is larger than the cutoff.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Top1 {
+    // The largest value seen, or the cutoff if no larger value has been seen.
+    value: u32,
+    // Only values larger than this will be considered.
+    cutoff: u32,
+}
+
+impl Top1 {
+    /// Returns a new instance of Top1.
+    /// Only values larger than the cutoff will be considered.
+    pub fn new(cutoff: u32) -> Self {
+        Self {
+            value: cutoff,
+            cutoff,
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> u32 {
+        self.cutoff
+    }
+
+    /// Returns the current threshold: a value must be larger than this to be admitted.
+    /// It is the retained value, if there is one, and otherwise the cutoff.
+    #[inline]
+    pub fn threshold(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns the largest value seen, or None if none were larger than the cutoff.
+    #[inline]
+    pub fn max(&self) -> Option<u32> {
+        (self.value > self.cutoff).then_some(self.value)
+    }
+
+    /// Returns an Iterator over the retained value, if there is one.
+    #[inline]
+    pub fn iter(&self) -> option::IntoIter<u32> {
+        self.max().into_iter()
+    }
+
+    /// Considers a new value, and keeps it if it is the largest so far.
+    #[inline]
+    pub fn see(&mut self, value: u32) {
+        if value > self.value {
+            self.value = value;
+        }
+    }
+
+    /// Changes the cutoff value to the specified new value.
+    /// As with a Top16, this removes the value if it is not larger than the new cutoff.
+    pub fn set_cutoff(&mut self, new_cutoff: u32) {
+        if self.value <= self.cutoff || self.value <= new_cutoff {
+            self.value = new_cutoff;
+        }
+        self.cutoff = new_cutoff;
+    }
+}
@@ -14,10 +14,14 @@
 //! will be considered.  So, for example, if you are using u32 values
 //! and specify 0 as the cutoff, then 0s will never be included in the result,
 //! even if all the values seen were 0.
-//! If you really need to include 0s in the result,
-//! you can use Option<u32> values with None as the cutoff value.
-//! Or you could use (u32, u32) values, where the second u32 is a counter,
+//! If you really need to include 0s in the result, you can use a TopFewUnbounded,
+//! or `Option<u32>` values with None as the cutoff value.
+//! Or you could use `(u32, u32)` values, where the second u32 is a counter,
 //! with (0,0) as the cutoff.  Or you could use i32 values with -1 as the cutoff.
+//! Top16 is the u32 version of TopFew, which works with any type that is Ord and Copy,
+//! so those are just `TopFew<Option<u32>>`, `TopFew<(u32, u32)>` and `TopFew<i32>`.
+//! The values are compared using their own ordering, so e.g. -5 is below 3 in a `TopFew<i32>`.
+//! To keep the smallest values instead, use `TopFew<T, N, Min>`; see the order module.
 //!
 //! Having a cutoff value helps performance in a few ways.
 //! We initialize the list to the cutoff value, so we always have 16 values,
@@ -31,6 +35,9 @@
 //! If a given value is seen multiple times, it can be included multiple times.
 //! New values do not replace existing values; the oldest instances are retained,
 //! and are reported first by iterators.
+//! If you would rather have newer values win ties, use the NewestWins order,
+//! and if you only want distinct values, use TopFewDistinct.
+//! To also have an upper bound on the values, use TopFewBand.
 //!
 //! Top16 is designed for streaming use cases, where you show it values as they
 //! come in, and it keeps track of the top 16 values seen so far.
@@ -43,90 +50,614 @@
 //! get them in ascending order.  Note that you will get less than 16 values
 //! if it has not seen 16 values larger than the cutoff.
 
-use std::fmt::Debug;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
 
-const NUM: usize = 16; // number of elements and indices
-const IX_BITS: u32 = 4; // bits to hold an index
-const IX_MASK: u64 = (1 << IX_BITS) - 1; // mask for extracting an index, e.g. 0xF
-const IXS_BITS: u32 = NUM as u32 * IX_BITS; // 64 bits for 16 indices
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, vec::Vec};
 
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use crate::ixs::{Cap, Capacity, IndexStore};
+use crate::order::{Max, Order};
+
+// The store of sorted indices for a TopFew with N elements.
+type Store<const N: usize> = <Cap<N> as Capacity>::Store;
+
+/// Keeps track of the top N values of type T seen so far, 16 unless you say otherwise.
+/// Any type that is Ord and Copy will do, e.g. i32 or (u32, u32);
+/// see the module documentation for the details.
+/// N can be anything from 1 to 25, and the documentation for the methods,
+/// which talks about a Top16, applies the same way to the other sizes.
+/// O is the order, Max unless you say otherwise; with Min it keeps the smallest values,
+/// and "larger" in the documentation means ranking higher (see the order module).
 #[derive(Clone, Copy)]
-pub struct Top16 {
+pub struct TopFew<T, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
     // A value must be larger than this to be included in the top list.
     // It is the smallest value in the list, or the cutoff value
     // if the list has not been filled yet.
-    threshold: u32,
+    threshold: T,
     // The cutoff value.  Only values larger than this will be considered,
     // or returned by the iterator.
-    cutoff: u32,
-    // The 4-bit indices of the top elements, packed in ascending order;
+    cutoff: T,
+    // The indices of the top elements, packed in ascending order;
     // the least significant bits contain the index of the smallest, etc.
-    sorted_ixs: u64,
+    sorted_ixs: Store<N>,
     // The top elements, unordered.
-    elements: [u32; NUM],
+    elements: [T; N],
+    // The largest value ever seen that was larger than the cutoff at the time.
+    #[cfg(feature = "counters")]
+    max_ever: Option<T>,
+    // The order, which only exists at compile time.
+    order: PhantomData<O>,
 }
 
-impl Top16 {
+/// The top 16 u32 values, which is what the crate was originally built for.
+pub type Top16 = TopFew<u32, 16, Max>;
+
+/// The top N u32 values.
+pub type TopN<const N: usize> = TopFew<u32, N>;
+
+/// The top 8 u32 values, whose indices fit in a u32.
+pub type Top8 = TopN<8>;
+
+impl<T, const N: usize, O: Order> TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// The maximum number of values it can hold, e.g. 16 for a Top16.
+    pub const CAPACITY: usize = N;
+
+    // The number of bits that hold an index.
+    const IX_BITS: u32 = Store::<N>::IX_BITS;
+    // The number of bits that hold all of the indices, e.g. 64 for 16 indices.
+    const IXS_BITS: u32 = N as u32 * Self::IX_BITS;
+    // The mask for a single index.
+    const IX_MASK: u32 = (1 << Self::IX_BITS) - 1;
+
+    // The packing that the search, the iterators and element_at() depend on.
+    // A const is only evaluated when it is used, so new() refers to this,
+    // which makes it a compile-time error to create a TopFew whose N breaks it.
+    const PACKING_IS_VALID: () = {
+        assert!(N >= 1, "a TopFew must have room for at least one value");
+        // Every slot's index fits in IX_BITS, i.e. under the mask that ix() applies.
+        assert!(
+            N as u32 - 1 <= Self::IX_MASK,
+            "indices do not fit in IX_BITS"
+        );
+        // All of the indices fit in the store.
+        assert!(
+            N <= Store::<N>::SLOTS,
+            "the store has no room for N indices"
+        );
+        assert!(Self::IXS_BITS == N as u32 * Self::IX_BITS);
+        assert!(Self::IXS_BITS <= u128::BITS);
+    };
+}
+
+// A Top16 packs its sixteen 4-bit indices into exactly 64 bits.
+const _: () = {
+    assert!(Top16::IX_BITS == 4);
+    assert!(Top16::IX_MASK == 0xF);
+    assert!(Top16::IXS_BITS == 64);
+    assert!(Top8::IXS_BITS == 32);
+};
+
+impl<T: Ord + Copy, const N: usize, O: Order> TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
     /// Returns a new instance of Top16.
-    /// Only values larger than the cutoff will be considered.
-    pub fn new(cutoff: u32) -> Self {
+    /// Only values larger than the cutoff will be considered,
+    /// so with a cutoff of u32::MAX it always stays empty.
+    pub fn new(cutoff: T) -> Self {
+        let () = Self::PACKING_IS_VALID;
         Self {
-            elements: [cutoff; NUM],
-            sorted_ixs: 0xFEDCBA9876543210,
+            elements: [cutoff; N],
+            sorted_ixs: Store::<N>::IDENTITY,
             threshold: cutoff,
             cutoff,
+            #[cfg(feature = "counters")]
+            max_ever: None,
+            order: PhantomData,
+        }
+    }
+
+    /// Returns a new instance of Top16 holding the top 16 values of a slice
+    /// that is already sorted in descending order, e.g. the result of an earlier query.
+    /// Only values larger than the cutoff will be considered.
+    /// This takes the values directly rather than searching for each one's position,
+    /// and gives the same result as seeing the values in order.
+    /// In debug builds, it panics if the slice is not sorted in descending order.
+    pub fn from_sorted_desc(cutoff: T, sorted_desc: &[T]) -> Self {
+        debug_assert!(
+            sorted_desc.windows(2).all(|w| O::ge(&w[0], &w[1])),
+            "slice is not sorted in descending order"
+        );
+        // With the indices in their initial order, the elements must be in ascending order,
+        // so we put the largest value in the last slot and work down from there.
+        // Any slots we don't fill keep the cutoff value.
+        let mut top = Self::new(cutoff);
+        let values = sorted_desc.iter().take_while(|&&v| O::gt(&v, &cutoff));
+        for (slot, &v) in top.elements.iter_mut().rev().zip(values) {
+            *slot = v;
+        }
+        top.threshold = top.element_at(0);
+        top
+    }
+
+    /// Clears out all of the values and sets a new cutoff, leaving this TopFew
+    /// as it was when it was created with that cutoff.
+    /// The cutoff is exclusive, as for new(), even for a Top16 from new_inclusive(),
+    /// so to keep including values equal to c, pass c - 1.
+    pub fn reinit(&mut self, cutoff: T) {
+        *self = Self::new(cutoff);
+    }
+
+    /// Returns a guard that you can see values through, and that calls reinit()
+    /// with the current cutoff when it is dropped, e.g. so that each iteration
+    /// of a measurement loop starts out empty without having to remember to clear it.
+    /// A cutoff set through the guard lasts only until then.
+    pub fn scoped(&mut self) -> ScopeGuard<'_, T, N, O> {
+        ScopeGuard {
+            cutoff: self.cutoff,
+            top: self,
         }
     }
 
     /// Changes the cutoff value to the specified new value.
     /// Note that this removes values that are smaller than the new cutoff.
-    pub fn set_cutoff(&mut self, new_cutoff: u32) {
-        // If the cutoff is being raised, then we need to set any values
-        // that are smaller than the new cutoff to the new cutoff.
-        // If the cutoff is being lowered, then we need to set any values
-        // equal to the old cutoff to the new lower cutoff.
-        // We can do both in one go.
-        let cutoff = self.cutoff.max(new_cutoff - 1);
-        let mut shift = 0u32;
-        loop {
-            if shift >= IXS_BITS {
-                break; // We have processed all indices.
+    /// Lowering the cutoff does not bring anything back: values that were not larger
+    /// than the old cutoff were never retained, so the ones between the two cutoffs
+    /// are missing, and the TopFew only has what it would have had with the lower cutoff
+    /// all along if can_lower_cutoff() returned true beforehand.
+    /// The new cutoff is exclusive, as for new(), even for a Top16 from new_inclusive(),
+    /// so to keep including values equal to c, pass c - 1.
+    pub fn set_cutoff(&mut self, new_cutoff: T) {
+        if O::gt(&new_cutoff, &self.cutoff) {
+            // The cutoff is being raised, so we need to set any values that are
+            // not larger than the new cutoff to the new cutoff, working up from the bottom.
+            // (Comparing against new_cutoff - 1 instead would underflow for 0.)
+            let mut shift = 0u32;
+            while shift < Self::IXS_BITS {
+                let ix = self.ix(shift);
+                if O::gt(&self.elements[ix], &new_cutoff) {
+                    break; // All remaining elements are larger; keep them.
+                }
+                self.elements[ix] = new_cutoff;
+                shift += Self::IX_BITS; // On to the next larger element's index.
             }
+            self.threshold = self.element_at(0);
+        } else if !self.can_lower_cutoff() {
+            // The cutoff is being lowered and there are unfilled slots.  They are
+            // the ones at the bottom that hold the old cutoff, and only they change;
+            // since there is at least one, the new cutoff becomes the threshold.
+            let mut shift = 0u32;
+            while shift < Self::IXS_BITS && self.element_at(shift) == self.cutoff {
+                let ix = self.ix(shift);
+                self.elements[ix] = new_cutoff;
+                shift += Self::IX_BITS;
+            }
+            self.threshold = new_cutoff;
+        } else {
+            // It is being lowered but every slot holds a real value, so only the threshold
+            // can change, if set_min_threshold() had raised it.
+            self.threshold = self.element_at(0);
+        }
+        self.cutoff = new_cutoff;
+    }
+
+    /// Returns true if lowering the cutoff now, with set_cutoff(), would leave this TopFew
+    /// as it would have been with the lower cutoff all along.  That is the case when
+    /// it is full, i.e. it has 16 values larger than the cutoff: any values it missed
+    /// because they were not larger than the cutoff would not have made the top 16.
+    /// Otherwise there may have been some between the two cutoffs that it did not keep.
+    #[inline]
+    pub fn can_lower_cutoff(&self) -> bool {
+        // Not the threshold, which set_min_threshold() may have raised.
+        O::gt(&self.element_at(0), &self.cutoff)
+    }
+
+    /// Raises the cutoff to the smallest retained value, so that from then on a value
+    /// must beat everything that is currently retained to be considered.
+    /// Since only values larger than the cutoff are retained, that drops the smallest
+    /// value, along with any others equal to it.  If fewer than 16 values are retained,
+    /// the smallest "value" is the cutoff itself, so this does nothing.
+    pub fn raise_cutoff_to_current_min(&mut self) {
+        self.set_cutoff(self.element_at(0));
+    }
+
+    /// Discards all but the top k retained values, so that from then on
+    /// it behaves as if it had only seen those.
+    pub fn truncate_to(&mut self, k: usize) {
+        // The discarded values are the lowest ones, so they become cutoff values
+        // without changing the order.
+        let discard = N - k.min(N);
+        for shift in (0..discard as u32 * Self::IX_BITS).step_by(Self::IX_BITS as usize) {
+            let ix = self.ix(shift);
+            self.elements[ix] = self.cutoff;
+        }
+        self.threshold = self.element_at(0);
+    }
+
+    /// Removes the values for which keep returns false.
+    pub fn retain<F: Fn(T) -> bool>(&mut self, keep: F) {
+        self.retain_slots(|_, v| keep(v));
+    }
+
+    /// Replaces each retained value v with f(v), e.g. to rescale all of them at once,
+    /// without searching for their new positions.  That is only valid if f preserves
+    /// their order: if v1 < v2 then f(v1) <= f(v2), or else the top 16 are no longer
+    /// sorted and the TopFew stops working properly.  Use map_monotonic() to have
+    /// that checked in debug builds.
+    /// Values that f maps to the cutoff or below are removed.
+    pub fn map_in_place<F: Fn(T) -> T>(&mut self, f: F) {
+        for shift in (0..Self::IXS_BITS).step_by(Self::IX_BITS as usize) {
             let ix = self.ix(shift);
-            if self.elements[ix] > cutoff {
-                break; // All remaining elements are larger; keep them.
+            let v = self.elements[ix];
+            if O::gt(&v, &self.cutoff) {
+                let mapped = f(v);
+                // Anything at or below the cutoff becomes a cutoff value, which is
+                // still in order, since f keeps the smallest values at the bottom.
+                self.elements[ix] = if O::gt(&mapped, &self.cutoff) {
+                    mapped
+                } else {
+                    self.cutoff
+                };
             }
-            self.elements[ix] = new_cutoff;
-            shift += IX_BITS; // On to the next larger element's index.
         }
         self.threshold = self.element_at(0);
-        self.cutoff = new_cutoff;
     }
 
-    /// Returns the current cutoff value.
+    /// Does what map_in_place() does, and in debug builds, panics if f did not
+    /// preserve the order of the values.
+    pub fn map_monotonic<F: Fn(T) -> T>(&mut self, f: F) {
+        self.map_in_place(f);
+        debug_assert!(
+            (Self::IX_BITS..Self::IXS_BITS)
+                .step_by(Self::IX_BITS as usize)
+                .all(|shift| O::ge(
+                    &self.element_at(shift),
+                    &self.element_at(shift - Self::IX_BITS)
+                )),
+            "the function did not preserve the order of the values"
+        );
+    }
+
+    /// Returns the current cutoff value.  That is always exclusive, so for a Top16
+    /// from new_inclusive(c), it is c - 1 rather than the c that was passed in.
     #[inline]
-    pub fn cutoff(&self) -> u32 {
+    pub fn cutoff(&self) -> T {
         self.cutoff
     }
 
+    /// Returns the current threshold: a value must be larger than this to be admitted,
+    /// or with the NewestWins order, equal to it and larger than the cutoff.
+    /// It is the smallest retained value once 16 values larger than the cutoff
+    /// have been seen, and until then it is equal to the cutoff,
+    /// unless set_min_threshold() has raised it.
+    /// Reading it is cheap, so you can use it to skip computing values
+    /// that you can tell will not make it in.
+    #[inline]
+    pub fn threshold(&self) -> T {
+        self.threshold
+    }
+
+    /// Raises the threshold to the bound, if it is lower, without changing the cutoff
+    /// or the retained values, so that values that are not larger than the bound
+    /// are rejected by the cheap threshold check, e.g. for a batch in which
+    /// you know that only larger values matter.  The iterator is unaffected,
+    /// since it only looks at the cutoff.
+    ///
+    /// The raised threshold lasts until a value gets in: it still takes the slot of
+    /// the smallest value, or an unfilled slot, and the threshold goes back to being
+    /// the new smallest value, as it always is after an admission.  So the bound is
+    /// a hint for rejecting values, not a guarantee that nothing smaller is retained.
+    /// Methods that change the retained values other than by seeing them, such as
+    /// set_cutoff() and retain(), also put it back.  With the NewestWins order,
+    /// a value equal to the bound is admitted, as usual for a tie with the threshold.
+    #[inline]
+    pub fn set_min_threshold(&mut self, bound: T) {
+        if O::gt(&bound, &self.threshold) {
+            self.threshold = bound;
+        }
+    }
+
     /// Returns the largest element in the top 16.
     #[inline]
-    pub fn max(&self) -> Option<u32> {
-        let v = self.element_at(IXS_BITS - IX_BITS);
-        (v > self.cutoff).then_some(v)
+    pub fn max(&self) -> Option<T> {
+        let v = self.element_at(Self::IXS_BITS - Self::IX_BITS);
+        O::gt(&v, &self.cutoff).then_some(v)
+    }
+
+    /// Returns the number of retained values, i.e. those larger than the cutoff,
+    /// which is 16 unless fewer than 16 of them have been seen.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.iter().len()
+    }
+
+    /// Returns the number of distinct retained values, which is less than len()
+    /// if some of them are equal, e.g. 1 if all of them are, and 0 if there are none.
+    pub fn distinct_len(&self) -> usize {
+        // Equal values are next to each other in the sorted order,
+        // so each group is a transition from one value to a different one.
+        self.iter_with_tie_flags().filter(|&(_, tie)| !tie).count()
+    }
+
+    /// Returns the number of retained values equal to max(), or 0 if there are none.
+    /// That is count_equal() of the max, but it stops at the first smaller value.
+    /// If the stream is dominated by one value, that is e.g. 16 from the start,
+    /// and with the number of values seen it gives an idea of how common the max is.
+    pub fn max_multiplicity(&self) -> usize {
+        let Some(max) = self.max() else {
+            return 0;
+        };
+        self.iter().take_while(|&v| v == max).count()
+    }
+
+    /// Returns the maximum number of values it can hold, which is CAPACITY, e.g. 16.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Returns true if no values are retained, i.e. none larger than the cutoff
+    /// have been seen, whether or not any values have been seen at all.
+    /// It just checks the largest element, so it takes constant time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.max().is_none()
+    }
+
+    /// Returns the smallest and largest retained values, or None if there are none.
+    #[inline]
+    pub fn range(&self) -> Option<(T, T)> {
+        let mut iter = self.iter();
+        let max = iter.next()?;
+        Some((iter.next_back().unwrap_or(max), max))
+    }
+
+    /// Returns true if the largest retained value is larger than the specified value,
+    /// or false if no values are retained.  It takes constant time.
+    #[inline]
+    pub fn max_exceeds(&self, value: T) -> bool {
+        self.max().is_some_and(|max| O::gt(&max, &value))
+    }
+
+    /// Returns true if the smallest retained value is smaller than the specified value,
+    /// or false if no values are retained.  It takes constant time once 16 values
+    /// are retained; until then, it has to skip over the unfilled slots.
+    #[inline]
+    pub fn min_below(&self, value: T) -> bool {
+        self.iter()
+            .next_back()
+            .is_some_and(|min| O::gt(&value, &min))
+    }
+
+    /// Returns the largest value ever seen that was larger than the cutoff at the time,
+    /// or None if there has not been one, with the counters feature.
+    /// Seeing a value larger than max() always updates this; usually you do not
+    /// need it, since the largest value is never evicted.  But it differs from max()
+    /// once the largest value is removed, e.g. with set_cutoff(), retain() or
+    /// truncate_to(), which leave it alone, and in a TopFewBand it can be
+    /// a value that was too large to be admitted.  undo() does not change it either.
+    /// It is reset by reinit().
+    #[cfg(feature = "counters")]
+    #[inline]
+    pub fn max_ever(&self) -> Option<T> {
+        self.max_ever
+    }
+
+    /// Returns the largest element in the top 16 without checking it against the cutoff,
+    /// so it returns the cutoff value if no larger value has been seen.
+    /// Use this in hot loops where you know that the Top16 is not empty.
+    #[inline]
+    pub fn max_unchecked(&self) -> T {
+        self.element_at(Self::IXS_BITS - Self::IX_BITS)
+    }
+
+    /// Returns the retained value at the specified position in descending order,
+    /// so get(0) is the largest, or None if fewer than i + 1 values are retained.
+    #[inline]
+    pub fn get(&self, i: usize) -> Option<T> {
+        if i >= N {
+            return None;
+        }
+        let v = self.element_at(Self::IXS_BITS - (i as u32 + 1) * Self::IX_BITS);
+        O::gt(&v, &self.cutoff).then_some(v)
+    }
+
+    /// Returns the (k + 1)th largest retained value, or None if fewer than k + 1
+    /// values are retained.  This is the same as get(k), and like it takes constant time,
+    /// unlike iter().nth(k).
+    #[inline]
+    pub fn nth_largest(&self, k: usize) -> Option<T> {
+        self.get(k)
+    }
+
+    /// Returns all 16 elements in the order they happen to be stored in, which is arbitrary.
+    /// Slots that are not in use hold the cutoff value, so unless the TopFew is full,
+    /// some of the elements are not retained values, and you may need to filter them out.
+    /// This is meant for processing all of the elements at once, e.g. with SIMD.
+    #[inline]
+    pub fn elements_unordered(&self) -> &[T; N] {
+        &self.elements
+    }
+
+    /// Returns all 16 elements in the order they happen to be stored in, for changing
+    /// them in place.  Afterward, call resort() before doing anything else with the TopFew,
+    /// since the sorted indices do not know about the changes.
+    #[inline]
+    pub fn elements_unordered_mut(&mut self) -> &mut [T; N] {
+        &mut self.elements
+    }
+
+    /// Sorts the elements from scratch and fixes the threshold, e.g. after changing them
+    /// with elements_unordered_mut().  Elements that are not larger than the cutoff
+    /// become unfilled slots.
+    /// Equal values end up in an arbitrary order.
+    pub fn resort(&mut self) {
+        for slot in 0..N {
+            let v = self.elements[slot];
+            if !O::gt(&v, &self.cutoff) {
+                self.elements[slot] = self.cutoff;
+            }
+        }
+        let mut ixs: [usize; N] = core::array::from_fn(|ix| ix);
+        ixs.sort_unstable_by(|&a, &b| O::cmp(&self.elements[a], &self.elements[b]));
+        let mut packed = 0u128;
+        for (i, &ix) in ixs.iter().enumerate() {
+            packed |= (ix as u128) << (i as u32 * Self::IX_BITS);
+        }
+        self.sorted_ixs = IndexStore::from_u128(packed);
+        self.threshold = self.element_at(0);
+    }
+
+    /// Returns the internal state as (cutoff, threshold, sorted indices, elements),
+    /// e.g. for a compact serialization format; from_raw_parts() puts it back together.
+    /// For a Top16, the sorted indices are a u64 (on 64-bit targets).
+    #[inline]
+    pub fn raw_parts(&self) -> (T, T, <Cap<N> as Capacity>::Store, [T; N]) {
+        (self.cutoff, self.threshold, self.sorted_ixs, self.elements)
+    }
+
+    /// Reconstructs a TopFew from what raw_parts() returned, without checking it.
+    ///
+    /// # Safety
+    ///
+    /// The parts must have come from raw_parts() on a TopFew of the same type,
+    /// or otherwise satisfy the invariants that check_invariants() checks.
+    /// Other code in the crate is allowed to rely on those for memory safety,
+    /// e.g. that the sorted indices are a permutation of the slots.
+    #[inline]
+    pub unsafe fn from_raw_parts(
+        cutoff: T,
+        threshold: T,
+        sorted_ixs: <Cap<N> as Capacity>::Store,
+        elements: [T; N],
+    ) -> Self {
+        Self {
+            threshold,
+            sorted_ixs,
+            elements,
+            ..Self::new(cutoff)
+        }
+    }
+
+    /// Returns true if the value is one of the retained top values.
+    /// The cutoff value never is, since values equal to it are not retained.
+    #[inline]
+    pub fn contains(&self, value: T) -> bool {
+        self.slot_of(value).is_some()
+    }
+
+    /// Returns the position that a retained value equal to the specified one has now,
+    /// numbered as for rank(): 1 for the smallest element and 16 for the largest.
+    /// If several are equal to it, this is the lowest of their positions.
+    /// It returns None if no retained value is equal to it.
+    pub fn position_of(&self, value: T) -> Option<usize> {
+        if !O::gt(&value, &self.cutoff) {
+            return None;
+        }
+        (0..Self::IXS_BITS)
+            .step_by(Self::IX_BITS as usize)
+            .find(|&shift| self.element_at(shift) == value)
+            .map(|shift| (shift / Self::IX_BITS + 1) as usize)
+    }
+
+    /// Returns the number of retained values equal to the specified value.
+    /// That is always 0 for the cutoff value.
+    #[inline]
+    pub fn count_equal(&self, value: T) -> usize {
+        if O::gt(&value, &self.cutoff) {
+            self.elements.iter().filter(|&&v| v == value).count()
+        } else {
+            0
+        }
+    }
+
+    /// Checks the internal invariants, returning a description of the first
+    /// one that does not hold.  This is meant for tests and debugging,
+    /// e.g. of code that builds on the crate, not for the hot path.
+    #[cfg(feature = "alloc")]
+    pub fn check_invariants(&self) -> Result<(), String>
+    where
+        T: Debug,
+    {
+        // The sorted indices must be a permutation of the slots.
+        let mut seen = 0u32;
+        for shift in (0..Self::IXS_BITS).step_by(Self::IX_BITS as usize) {
+            seen |= 1 << self.ix(shift);
+        }
+        if seen != (1 << N) - 1 {
+            return Err(format!(
+                "sorted_ixs {:0w$X} is not a permutation",
+                self.sorted_ixs.to_u128(),
+                w = Self::IXS_BITS as usize / 4
+            ));
+        }
+        // The threshold must be the smallest element, or above it after set_min_threshold(),
+        // and the elements must be in order along the indices.
+        let min = self.element_at(0);
+        if O::gt(&min, &self.threshold) {
+            return Err(format!(
+                "threshold {:?} is below the smallest element {min:?}",
+                self.threshold
+            ));
+        }
+        for shift in (Self::IX_BITS..Self::IXS_BITS).step_by(Self::IX_BITS as usize) {
+            let (lower, v) = (
+                self.element_at(shift - Self::IX_BITS),
+                self.element_at(shift),
+            );
+            if O::gt(&lower, &v) {
+                return Err(format!("element {v:?} at shift {shift} is below {lower:?}"));
+            }
+        }
+        // Unused slots hold the cutoff, and nothing is smaller.
+        if O::gt(&self.cutoff, &min) {
+            return Err(format!(
+                "element {min:?} is below the cutoff {:?}",
+                self.cutoff
+            ));
+        }
+        Ok(())
     }
 
     // Returns the index at the specified shift in the sorted indices.
     #[inline]
     fn ix(&self, shift: u32) -> usize {
-        ((self.sorted_ixs >> shift) & IX_MASK) as usize
+        self.sorted_ixs.get(shift)
     }
 
     // Returns the element at the specified shift in the sorted indices.
     #[inline]
-    fn element_at(&self, shift: u32) -> u32 {
-        // TODO: check whether the optimizer can tell that this is always in bounds.
-        self.elements[self.ix(shift)]
+    fn element_at(&self, shift: u32) -> T {
+        let ix = self.ix(shift);
+        debug_assert!(ix < N, "index {ix} out of range");
+        if N == 1 << Self::IX_BITS {
+            // Since ix() masks the index to IX_BITS bits, the optimizer can usually tell
+            // that it is in bounds; e.g. each step of the search in an x86-64 release build
+            // of see_helper is just
+            //     and  eax, 15
+            //     cmp  esi, dword ptr [rdi + 4*rax + 64]
+            //     seta cl
+            // with no cmp/jae to a panic_bounds_check call either way.
+            // Using get_unchecked() just means that we don't depend on that.
+            // SAFETY: ix() returns IX_BITS bits, and N is 1 << IX_BITS, so ix < N.
+            unsafe { *self.elements.get_unchecked(ix) }
+        } else {
+            // The positions we read always hold a permutation of 0..N,
+            // but the mask alone does not show that, so we keep the bounds check.
+            self.elements[ix]
+        }
     }
 
     /// Considers a new value to see if is one of the top 16.
@@ -135,30 +666,332 @@ impl Top16 {
     /// element and 16 for the largest element.  That way you can, for example,
     /// easily trigger special behavior if the value is in the top 5.
     #[inline]
-    pub fn rank(&mut self, value: u32) -> usize {
-        // If the value is not greater than the threshold, then it is not in the top 16.
+    pub fn rank(&mut self, value: T) -> usize {
+        self.note_seen(value);
+        // Rejecting a value is just this compare and branch; the rest is in see_helper().
+        if !self.admits(value) {
+            0
+        } else {
+            (self.see_helper(value) / Self::IX_BITS + 1) as usize
+        }
+    }
+
+    /// Considers a new value, as see() does, and describes what happened:
+    /// whether it was rejected, added to a TopFew that had not filled up yet,
+    /// or admitted in place of the smallest value, which it returns.
+    /// The positions are as for rank(), 1 for the smallest element and 16 for the largest.
+    #[inline]
+    pub fn classify(&mut self, value: T) -> SeeOutcome<T> {
+        self.note_seen(value);
+        // If the value is not admitted by the threshold, then it is not in the top 16.
         // We separate this check from the rest of the logic so that it will be inlined.
-        if value <= self.threshold {
+        if !self.admits(value) {
+            SeeOutcome::Rejected
+        } else {
+            // The smallest element, rather than the threshold, which may have been raised.
+            let min = self.element_at(0);
+            let position = (self.see_helper(value) / Self::IX_BITS + 1) as usize;
+            if O::gt(&min, &self.cutoff) {
+                SeeOutcome::Admitted {
+                    position,
+                    evicted: min,
+                }
+            } else {
+                SeeOutcome::AddedNotFull { position }
+            }
+        }
+    }
+
+    /// Considers a new value, as rank() does, and also returns the value that it evicted.
+    /// If the rank is 0 then nothing was evicted, and if something was admitted but
+    /// nothing was evicted then fewer than 16 values had been retained.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> InsertResult<T> {
+        let outcome = self.classify(value);
+        InsertResult {
+            rank: outcome.position(),
+            evicted: outcome.evicted(),
+        }
+    }
+
+    /// Returns the position the value would have if it were seen now, as described
+    /// for rank(), but without adding it.
+    #[inline]
+    pub fn would_rank(&self, value: T) -> usize {
+        if !self.admits(value) {
             0
         } else {
-            ((self.see_helper(value) >> 2) + 1) as usize
+            (self.search(value) / Self::IX_BITS + 1) as usize
         }
     }
 
+    /// Returns true if seeing the value now would add it to the top 16.
+    /// This is the cheap check that see() and rank() start with, so you can use it
+    /// to skip fully computing a value when a cheap upper bound on it would not get in.
+    #[inline]
+    pub fn would_admit(&self, value: T) -> bool {
+        self.admits(value)
+    }
+
+    /// Returns the positions that each of the values would have if it were seen now,
+    /// as described for rank(), without adding any of them.  Each is considered
+    /// on its own, against the current contents.
+    #[cfg(feature = "alloc")]
+    pub fn ranks(&self, values: &[T]) -> Vec<usize> {
+        values.iter().map(|&v| self.would_rank(v)).collect()
+    }
+
     /// Considers a new value to see if is one of the top 16.
     /// If so, it is added to the list.
     #[inline]
-    pub fn see(&mut self, value: u32) {
-        // If the value is not greater than the threshold, then it is not in the top 16.
-        // We separate this check from the rest of the logic so that it will be inlined.
-        if value > self.threshold {
+    pub fn see(&mut self, value: T) {
+        self.note_seen(value);
+        // As in rank(), without working out the position.
+        if self.admits(value) {
+            self.see_helper(value);
+        }
+    }
+
+    /// Returns a copy of this TopFew that has also seen the value, as see() does.
+    /// Since a TopFew is Copy, this works with a Cell, e.g. `cell.set(cell.get().with_seen(v))`,
+    /// for when you have a shared reference rather than a mutable one.
+    #[inline]
+    pub fn with_seen(mut self, value: T) -> Self {
+        self.see(value);
+        self
+    }
+
+    /// Considers a new value, as see() does, and returns the threshold afterward,
+    /// so that you can keep your own copy of it, e.g. for an upstream filter.
+    /// Until 16 values larger than the cutoff have been seen, it is equal to the cutoff.
+    #[inline]
+    pub fn see_returning_threshold(&mut self, value: T) -> T {
+        self.see(value);
+        self.threshold
+    }
+
+    /// Considers each of the values in turn, as see() does, and returns
+    /// the number of them that were admitted to the top 16 when they were seen.
+    /// This is faster than calling see() for each value when most of them are rejected,
+    /// since rejecting a value is just a compare and branch.
+    pub fn see_all<I: IntoIterator<Item = T>>(&mut self, values: I) -> usize {
+        let mut admitted = 0;
+        if O::NEWEST_WINS {
+            for value in values {
+                self.note_seen(value);
+                if self.admits(value) {
+                    self.see_helper(value);
+                    admitted += 1;
+                }
+            }
+        } else {
+            // Keeping the threshold in a local lets it stay in a register,
+            // rather than being reloaded from self for every value.
+            let mut threshold = self.threshold;
+            for value in values {
+                self.note_seen(value);
+                if O::gt(&value, &threshold) {
+                    self.see_helper(value);
+                    threshold = self.threshold;
+                    admitted += 1;
+                }
+            }
+        }
+        admitted
+    }
+
+    /// Returns a copy of this TopFew, which you can later go back to with restore().
+    #[inline]
+    pub fn snapshot(&self) -> Self {
+        *self
+    }
+
+    /// Goes back to the state saved by snapshot().
+    #[inline]
+    pub fn restore(&mut self, snapshot: &Self) {
+        *self = *snapshot;
+    }
+
+    /// Considers a new value, as see() does, and returns a token that
+    /// undo() can use to reverse the effects.  That takes constant time,
+    /// and undoes just the parts that seeing a value can change, which is
+    /// cheaper than taking a snapshot when the values are large.
+    /// The token must be used on this TopFew before anything else changes it.
+    #[inline]
+    pub fn see_undoable(&mut self, value: T) -> UndoToken<T, N> {
+        // If the value is admitted, it goes in the smallest element's slot,
+        // so that is the only element that can change.
+        let slot = self.ix(0);
+        let token = UndoToken {
+            sorted_ixs: self.sorted_ixs,
+            slot,
+            evicted: self.elements[slot],
+            threshold: self.threshold,
+        };
+        self.see(value);
+        token
+    }
+
+    /// Reverses the effects of the see_undoable() call that returned the token.
+    #[inline]
+    pub fn undo(&mut self, token: UndoToken<T, N>) {
+        self.sorted_ixs = token.sorted_ixs;
+        self.elements[token.slot] = token.evicted;
+        self.threshold = token.threshold;
+    }
+
+    /// Considers each of the other TopFew's retained values, as see() does,
+    /// so that this one ends up with the top 16 of the values that both have seen.
+    /// Only values larger than this TopFew's cutoff are kept.
+    /// Since the result only depends on which values were seen, merging is
+    /// associative and commutative, so the TopFews can be merged in any grouping.
+    pub fn merge(&mut self, other: &Self) {
+        self.note_max_ever_of(other);
+        self.see_top_of(other, N as u32);
+    }
+
+    /// Considers the top n of the other TopFew's retained values, as see() does.
+    /// They are seen in descending order, after everything this one has already seen,
+    /// so when one of them is equal to a value already retained here, the one here
+    /// counts as older: it wins the tie, unless the order is NewestWins.
+    pub fn see_top_of(&mut self, other: &Self, n: u32) {
+        // The other's values come in descending order, so once one of them
+        // is not admitted, none of the rest will be either.
+        for value in other.take(n) {
+            self.note_seen(value);
+            if !self.admits(value) {
+                break;
+            }
             self.see_helper(value);
         }
     }
 
-    fn see_helper(&mut self, value: u32) -> u32 {
+    /// Returns the result of merging the other TopFew into this one; see merge().
+    pub fn merged(mut self, other: &Self) -> Self {
+        self.merge(other);
+        self
+    }
+
+    /// Merges all of the TopFews into the first one, as merge() does, and returns it,
+    /// or None if there are none.  This is for combining e.g. per-chunk results:
+    ///
+    /// ```
+    /// use top_few::Top16;
+    ///
+    /// let data: Vec<u32> = (0..1000).collect();
+    /// let top = Top16::merge_all(data.chunks(100).map(Top16::from)).unwrap();
+    /// assert_eq!(top, Top16::from(&data[..]));
+    /// ```
+    ///
+    /// For the result, which TopFew comes first only matters for its cutoff.
+    pub fn merge_all<I: IntoIterator<Item = Self>>(tops: I) -> Option<Self> {
+        tops.into_iter().reduce(|a, b| a.merged(&b))
+    }
+
+    /// Merges the other TopFew into this one, keeping the same values as merge(),
+    /// but by walking down both sorted sequences at once, as in a merge sort,
+    /// rather than searching for the position of each of the other's values.
+    /// Equal values from this TopFew are taken first, as merge() does,
+    /// unless the order is NewestWins, in which case the other's are.
+    /// That is quicker when both are full and many of the other's values make it in.
+    pub fn merge_sorted(&mut self, other: &Self) {
+        self.note_max_ever_of(other);
+        let cutoff = self.cutoff;
+        let mut ours = self.iter().peekable();
+        let mut theirs = other.iter().take_while(|v| O::gt(v, &cutoff)).peekable();
+        // As in from_sorted_desc(), the values go in the slots in ascending order,
+        // so that the indices can be put back in their initial order.
+        let mut elements = [cutoff; N];
+        let mut filled = 0;
+        while filled < N {
+            let take_ours = match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) => {
+                    if O::NEWEST_WINS {
+                        O::gt(a, b)
+                    } else {
+                        O::ge(a, b)
+                    }
+                }
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let value = if take_ours {
+                ours.next()
+            } else {
+                theirs.next()
+            }
+            .unwrap();
+            filled += 1;
+            elements[N - filled] = value;
+        }
+        self.elements = elements;
+        self.sorted_ixs = Store::<N>::IDENTITY;
+        self.threshold = self.element_at(0);
+    }
+
+    fn see_helper(&mut self, value: T) -> u32 {
+        let shift = self.search(value);
+
+        // The new value takes the smallest element's slot, so move that slot's
+        // index to the found shift.
+        let old_min_ix = self.ix(0); // Save index of smallest element
+        // The new smallest element is the new value if it went at the bottom,
+        // and otherwise the one that was second smallest.  Reading that now,
+        // rather than reading the new smallest element back once the indices
+        // have been updated, keeps it out of the chain of dependent loads.
+        let threshold = if N == 1 || shift == 0 {
+            value
+        } else {
+            self.element_at(Self::IX_BITS)
+        };
+        self.sorted_ixs.promote_min(shift);
+
+        // eprintln!("shift: {shift:2}, ixs: {:016X}", self.sorted_ixs);
+
+        // Replace the smallest element with the new value and fix the threshold.
+        self.elements[old_min_ix] = value;
+        self.threshold = threshold; // >= the previous value, unless that was raised
+
+        // dbg!(&self.elements[0..4]);
+        shift
+    }
+
+    // Returns true if the threshold lets the value in.
+    #[inline]
+    fn admits(&self, value: T) -> bool {
+        O::gt(&value, &self.threshold)
+            || (O::NEWEST_WINS && value == self.threshold && O::gt(&value, &self.cutoff))
+    }
+
+    // Keeps track of the largest value seen, with the counters feature;
+    // otherwise this does nothing, and does not even look at the value.
+    #[inline(always)]
+    #[cfg_attr(not(feature = "counters"), allow(unused_variables))]
+    pub(crate) fn note_seen(&mut self, value: T) {
+        #[cfg(feature = "counters")]
+        if O::gt(&value, &self.cutoff) && self.max_ever.is_none_or(|max| O::gt(&value, &max)) {
+            self.max_ever = Some(value);
+        }
+    }
+
+    // Takes the other's max_ever into account, when merging it into this one.
+    #[inline(always)]
+    #[cfg_attr(not(feature = "counters"), allow(unused_variables))]
+    pub(crate) fn note_max_ever_of(&mut self, other: &Self) {
+        #[cfg(feature = "counters")]
+        if let Some(max) = other.max_ever {
+            self.note_seen(max);
+        }
+    }
+
+    // Returns the shift at which an admitted value belongs
+    // once the smallest element has been removed.
+    #[cfg(not(feature = "linear_search"))]
+    #[inline]
+    fn search(&self, value: T) -> u32 {
         // Perform a binary search to find the bit position for the new value's index
-        // among the sorted indices.  This diagram depicts the search pattern.
+        // among the sorted indices.  This diagram depicts the search pattern for a Top16.
         // 0    4    8    12   16   20   24   28   32   36   40   44   48   52   56   60
         // xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx xxxx
         //                                         ^
@@ -167,82 +1000,621 @@ impl Top16 {
         //      ^         .
         // ^    .
         //
-        // Since we always have 16 elements, we can unroll the loop
-        // and do log2(16) = 4 iterations of the binary search.
-        // We avoid branches by using branchless programming techniques.
-        // No += here because the RHS could be negative; we want to use u32s.
-        let mut shift = 32u32;
-        let le = |shift| (value <= self.element_at(shift)) as u32;
-        // shift = shift + a.cmp(b) as u64 * 4 * IX_BITS;  // << 4;
-        #[allow(clippy::identity_op, clippy::erasing_op)]
-        {
-            shift = shift + 4 * IX_BITS - (le(shift) << 5); //   - (0 | 8) * IX_BITS
-            shift = shift + 2 * IX_BITS - (le(shift) << 4); //   - (0 | 4) * IX_BITS
-            shift = shift + 1 * IX_BITS - (le(shift) << 3); //   - (0 | 2) * IX_BITS
-            shift = shift + 0 * IX_BITS - (le(shift) << 2); //   - (0 | 1) * IX_BITS
-        }
-
-        // Insert the new value's index at the found shift.
-        // E.g. if shift = 48 and sorted_ixs = 0xFEDCBA9876543210,
-        // upper = 0xFEDCBA9876543210 >> 52       = 0x0000000000000FED
-        // lower = 0xFEDCBA9876543210 << 12 >> 16 = 0x0000CBA987654321
-        // sorted_ixs                             = 0xFED0CBA987654321
-        //                        inserted value's index ^
-        // Note that we have to include the index at shift in lower,
-        // and we have to get rid of the smallest element's index,
-        // which is in the least significant 4 bits of sorted_ixs.
-        let lower =
-            (self.sorted_ixs << (IXS_BITS - IX_BITS - shift)).unbounded_shr(IXS_BITS - shift);
-        let upper = self.sorted_ixs.unbounded_shr(shift + IX_BITS);
-        let old_min_ix = self.ix(0); // Save index of smallest element
-        self.sorted_ixs = (((upper << IX_BITS) | (old_min_ix as u64)) << shift) | lower;
-
-        // eprintln!("shift: {shift:2}, ixs: {:016X}", self.sorted_ixs);
-
-        // Replace the smallest element with the new value and fix the threshold.
-        self.elements[old_min_ix] = value;
-        self.threshold = self.element_at(0); // always >= the previous value
+        // The new value is larger than the smallest element, which is being removed,
+        // so there are N positions it can go in.  Each step compares it with the element
+        // halfway through the positions that are left, and moves up if it belongs above,
+        // so for a Top16 there are log2(16) = 4 steps.  Since N is a constant,
+        // the loop is unrolled, and we avoid branches by multiplying the step
+        // by the result of the comparison.
+        let mut pos = 0u32;
+        let mut size = N as u32;
+        while size > 1 {
+            let half = size / 2;
+            let e = self.element_at((pos + half) * Self::IX_BITS);
+            // A new value goes below an equal element unless newer values win ties.
+            let above = if O::NEWEST_WINS {
+                O::ge(&value, &e)
+            } else {
+                O::gt(&value, &e)
+            };
+            pos += half * above as u32;
+            // If the value is below, this leaves an extra position when size is odd,
+            // which is harmless since the value does not belong there.
+            size -= half;
+        }
+        pos * Self::IX_BITS
+    }
 
-        // dbg!(&self.elements[0..4]);
+    // The straightforward alternative to the search above, for benchmarks to compare
+    // against: walk up from the bottom until reaching an element the value goes below.
+    // Compare them with `just bench-linear-search`, which runs the worst_case benchmarks
+    // with and without it.
+    #[cfg(feature = "linear_search")]
+    #[inline]
+    fn search(&self, value: T) -> u32 {
+        let mut shift = 0;
+        while shift + Self::IX_BITS < Self::IXS_BITS {
+            let e = self.element_at(shift + Self::IX_BITS);
+            let above = if O::NEWEST_WINS {
+                O::ge(&value, &e)
+            } else {
+                O::gt(&value, &e)
+            };
+            if !above {
+                break;
+            }
+            shift += Self::IX_BITS;
+        }
         shift
     }
 
     /// Returns an Iterator over the top 16 elements (or less if there are less), in descending order.
     #[inline]
-    pub fn iter(&self) -> Iter<'_> {
-        self.make_iter(0)
+    pub fn iter(&self) -> Iter<'_, T, N, O> {
+        self.make_iter(0, self.cutoff)
+    }
+
+    /// Returns an Iterator over just the retained values that are larger than the bound,
+    /// in descending order, e.g. to look at the ones over some threshold of interest.
+    /// Like the one from iter(), it is double-ended, and it never returns values
+    /// that are not larger than the cutoff, even if the bound is smaller.
+    #[inline]
+    pub fn iter_above(&self, bound: T) -> Iter<'_, T, N, O> {
+        let bound = if O::gt(&bound, &self.cutoff) {
+            bound
+        } else {
+            self.cutoff
+        };
+        self.make_iter(0, bound)
+    }
+
+    /// Returns an Iterator over the (rank, value) pairs of the retained values,
+    /// in descending order, where the ranks are what rank() returned for them:
+    /// 16 for the largest, and on down, so they end above 1 unless the Top16 is full.
+    pub fn iter_ranked(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (usize, T)> + ExactSizeIterator + '_ {
+        let iter = self.iter();
+        (iter.fwd_shift..iter.bwd_shift)
+            .step_by(Self::IX_BITS as usize)
+            .rev()
+            .map(|shift| ((shift / Self::IX_BITS + 1) as usize, self.element_at(shift)))
+    }
+
+    /// Returns an Iterator over the distinct retained values, in descending order,
+    /// each with the number of retained values equal to it.
+    pub fn iter_grouped(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        // Equal values are next to each other in the sorted order.
+        let mut iter = self.iter().peekable();
+        core::iter::from_fn(move || {
+            let value = iter.next()?;
+            let mut count = 1;
+            while iter.next_if_eq(&value).is_some() {
+                count += 1;
+            }
+            Some((value, count))
+        })
+    }
+
+    /// Returns the number of retained values that the other TopFew also retains,
+    /// counting a value that one retains several times as often as both retain it,
+    /// i.e. the size of the intersection of the two as multisets.
+    /// E.g. [5, 5, 5, 3] and [5, 5, 4] overlap by 2.  See set_overlap() for sets.
+    pub fn overlap(&self, other: &Self) -> usize {
+        self.common_groups(other).map(|(a, b)| a.min(b)).sum()
+    }
+
+    /// Returns the number of distinct values that both TopFews retain,
+    /// however many times each retains them.
+    /// E.g. [5, 5, 5, 3] and [5, 5, 4] have a set overlap of 1.
+    pub fn set_overlap(&self, other: &Self) -> usize {
+        self.common_groups(other).count()
+    }
+
+    // Returns the counts of each value that both retain, from iter_grouped().
+    // Both are in descending order, so this is a single pass down each.
+    fn common_groups<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let mut ours = self.iter_grouped().peekable();
+        let mut theirs = other.iter_grouped().peekable();
+        core::iter::from_fn(move || {
+            loop {
+                let (&(a, a_count), &(b, b_count)) = (ours.peek()?, theirs.peek()?);
+                match O::cmp(&a, &b) {
+                    Ordering::Greater => {
+                        ours.next();
+                    }
+                    Ordering::Less => {
+                        theirs.next();
+                    }
+                    Ordering::Equal => {
+                        ours.next();
+                        theirs.next();
+                        return Some((a_count, b_count));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns an Iterator over the retained values, in descending order, each with
+    /// whether it is equal to the one before it, e.g. for grouping ties in a leaderboard.
+    /// The flag is always false for the first value.
+    pub fn iter_with_tie_flags(&self) -> impl Iterator<Item = (T, bool)> + '_ {
+        let mut previous = None;
+        self.iter().map(move |value| {
+            let tie = previous == Some(value);
+            previous = Some(value);
+            (value, tie)
+        })
     }
 
     /// Returns an Iterator over the top n elements (or less if there are less), in descending order.
     /// top16.take(n) is equivalent to top16.iter().take(n), but more efficient.
     #[inline]
-    pub fn take(&self, n: u32) -> Iter<'_> {
-        self.make_iter((16 - 16.min(n)) * IX_BITS)
+    pub fn take(&self, n: u32) -> Iter<'_, T, N, O> {
+        self.make_iter((N as u32 - (N as u32).min(n)) * Self::IX_BITS, self.cutoff)
+    }
+
+    /// Returns the retained values (i.e. those above the cutoff) in descending order.
+    /// The Vec is allocated with exactly the right capacity.
+    #[cfg(feature = "alloc")]
+    pub fn to_sorted_vec(&self) -> Vec<T> {
+        let iter = self.iter();
+        let mut v = Vec::with_capacity(iter.len());
+        v.extend(iter);
+        v
+    }
+
+    /// Returns the retained values (i.e. those above the cutoff) in ascending order.
+    /// The Vec is allocated with exactly the right capacity.
+    #[cfg(feature = "alloc")]
+    pub fn to_sorted_vec_asc(&self) -> Vec<T> {
+        let iter = self.iter().rev();
+        let mut v = Vec::with_capacity(iter.len());
+        v.extend(iter);
+        v
+    }
+
+    /// Returns all 16 elements in descending order, without allocating.
+    /// Unless the TopFew is full, the last ones are unfilled slots, which hold the cutoff.
+    #[inline]
+    pub fn to_array_desc(&self) -> [T; N] {
+        core::array::from_fn(|i| self.element_at(Self::IXS_BITS - (i as u32 + 1) * Self::IX_BITS))
     }
 
-    // Does the actual work of creating an iterator.
-    fn make_iter(&self, mut fwd_shift: u32) -> Iter<'_> {
-        // Have to skip over any cutoff values (there shouldn't be anything lower).
-        while fwd_shift < IXS_BITS && self.element_at(fwd_shift) <= self.cutoff {
-            fwd_shift += IX_BITS;
+    /// Returns all 16 elements in ascending order, without allocating.
+    /// Unless the TopFew is full, the first ones are unfilled slots, which hold the cutoff.
+    #[inline]
+    pub fn to_array_asc(&self) -> [T; N] {
+        core::array::from_fn(|i| self.element_at(i as u32 * Self::IX_BITS))
+    }
+
+    /// Writes the retained values into the start of out, in descending order,
+    /// and returns the number of them, without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if out is shorter than the number of retained values,
+    /// so it should be at least 16 long.
+    pub fn copy_sorted_into(&self, out: &mut [T]) -> usize {
+        let iter = self.iter();
+        let n = iter.len();
+        for (slot, v) in out[..n].iter_mut().zip(iter) {
+            *slot = v;
+        }
+        n
+    }
+
+    // Does the actual work of creating an iterator over the values larger than the bound,
+    // which is normally the cutoff.
+    fn make_iter(&self, mut fwd_shift: u32, bound: T) -> Iter<'_, T, N, O> {
+        // Have to skip over any values up to the bound, e.g. cutoff values
+        // (there shouldn't be anything lower than those).
+        while fwd_shift < Self::IXS_BITS && !O::gt(&self.element_at(fwd_shift), &bound) {
+            fwd_shift += Self::IX_BITS;
         }
         Iter {
             top: self,
             fwd_shift,
-            bwd_shift: IXS_BITS,
+            bwd_shift: Self::IXS_BITS,
+        }
+    }
+
+    // The methods below let wrapper types keep their own data for each retained
+    // element, in arrays parallel to elements.  A slot is an index into elements.
+
+    // Like see(), but returns the slot the value was stored in, if it was admitted.
+    // Whatever the wrapper had for that slot belonged to the evicted element.
+    #[inline]
+    pub(crate) fn see_slot(&mut self, value: T) -> Option<usize> {
+        self.note_seen(value);
+        if self.admits(value) {
+            let shift = self.see_helper(value);
+            Some(self.ix(shift))
+        } else {
+            None
+        }
+    }
+
+    // Returns the slot of a retained element equal to the value, if there is one.
+    #[inline]
+    pub(crate) fn slot_of(&self, value: T) -> Option<usize> {
+        if O::gt(&value, &self.cutoff) {
+            self.elements.iter().position(|&v| v == value)
+        } else {
+            None
+        }
+    }
+
+    // Removes the elements for which keep(slot, value) returns false.
+    pub(crate) fn retain_slots(&mut self, keep: impl Fn(usize, T) -> bool) {
+        // Removed elements become cutoff values, which belong at the bottom,
+        // so we rebuild the sorted indices with the removed elements' indices first,
+        // followed by the kept ones in their existing order.
+        let mut removed_ixs = 0u128;
+        let mut removed_shift = 0u32;
+        let mut kept_ixs = 0u128;
+        let mut kept_shift = 0u32;
+        for shift in (0..Self::IXS_BITS).step_by(Self::IX_BITS as usize) {
+            let ix = self.ix(shift);
+            let v = self.elements[ix];
+            if O::gt(&v, &self.cutoff) && keep(ix, v) {
+                kept_ixs |= (ix as u128) << kept_shift;
+                kept_shift += Self::IX_BITS;
+            } else {
+                self.elements[ix] = self.cutoff;
+                removed_ixs |= (ix as u128) << removed_shift;
+                removed_shift += Self::IX_BITS;
+            }
+        }
+        self.sorted_ixs =
+            IndexStore::from_u128(removed_ixs | kept_ixs.unbounded_shl(removed_shift));
+        self.threshold = self.element_at(0);
+    }
+
+    // Returns the (slot, value) pairs of the retained elements, in descending order.
+    pub(crate) fn slots(&self) -> impl DoubleEndedIterator<Item = (usize, T)> + '_ {
+        let iter = self.iter();
+        (iter.fwd_shift..iter.bwd_shift)
+            .step_by(Self::IX_BITS as usize)
+            .rev()
+            .map(|shift| (self.ix(shift), self.element_at(shift)))
+    }
+}
+
+impl<const N: usize, O: Order<Direction = Max>> TopFew<u32, N, O>
+where
+    Cap<N>: Capacity,
+{
+    /// Returns a new instance of Top16 that considers values greater than
+    /// *or equal to* the cutoff.
+    /// This is just a Top16 with cutoff - 1 as its (exclusive) cutoff, so the unused slots
+    /// hold cutoff - 1, which is what the iterator skips over, and that is also what
+    /// cutoff() returns.  All values equal to the cutoff are real and are returned.
+    /// Nothing records that the cutoff was inclusive, so set_cutoff() and reinit()
+    /// take an exclusive cutoff as usual; pass them cutoff - 1 to keep including it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cutoff is 0, since there is no smaller u32 to fill the unused slots.
+    /// If you need to include 0s, see the module documentation.
+    pub fn new_inclusive(cutoff: u32) -> Self {
+        let Some(exclusive) = cutoff.checked_sub(1) else {
+            panic!("an inclusive cutoff of 0 would include every u32");
+        };
+        Self::new(exclusive)
+    }
+
+    /// Considers each of the values in the slice in turn, as see_all() does, and returns
+    /// the number of them that were admitted.  With the simd feature, which needs
+    /// a nightly compiler, it compares 8 values at a time against the threshold,
+    /// and only looks at them one at a time if any of them might get in,
+    /// which takes about half the time on the random_data benchmark.
+    /// Without it, this is just see_all().
+    #[inline]
+    pub fn see_slice(&mut self, values: &[u32]) -> usize {
+        #[cfg(feature = "simd")]
+        {
+            self.see_slice_simd(values)
+        }
+        #[cfg(not(feature = "simd"))]
+        {
+            self.see_all(values.iter().copied())
         }
     }
+
+    #[cfg(feature = "simd")]
+    fn see_slice_simd(&mut self, values: &[u32]) -> usize {
+        use core::simd::{Simd, cmp::SimdPartialOrd, num::SimdUint};
+        let chunks = values.chunks_exact(8);
+        let rest = chunks.remainder();
+        let mut admitted = 0;
+        for chunk in chunks {
+            let lanes = Simd::<u32, 8>::from_slice(chunk);
+            // The largest of them is the only one that could be the largest ever seen.
+            self.note_seen(lanes.reduce_max());
+            let threshold = Simd::splat(self.threshold);
+            // A tie with the threshold can get in with the NewestWins order.
+            let passed = if O::NEWEST_WINS {
+                lanes.simd_ge(threshold)
+            } else {
+                lanes.simd_gt(threshold)
+            };
+            if passed.any() {
+                // Admitting one raises the threshold, so check each of them again.
+                for &value in chunk {
+                    if self.admits(value) {
+                        self.see_helper(value);
+                        admitted += 1;
+                    }
+                }
+            }
+        }
+        admitted + self.see_all(rest.iter().copied())
+    }
+
+    /// Returns the smallest value that would be admitted if it were seen now,
+    /// e.g. to tell whatever produces the values not to bother with smaller ones.
+    /// That is threshold() + 1, or threshold() itself with the NewestWins order
+    /// once it is full, since a tie then gets in.  If the threshold is u32::MAX,
+    /// nothing more can be admitted, and it returns u32::MAX anyway.
+    /// TopFewDistinct and TopFewBand have their own, which also take the retained
+    /// values or the ceiling into account.
+    #[inline]
+    pub fn next_admission_value(&self) -> u32 {
+        if O::NEWEST_WINS && self.threshold > self.cutoff {
+            self.threshold
+        } else {
+            self.threshold.saturating_add(1)
+        }
+    }
+
+    /// Considers a new value, as see() does, and returns true if it was admitted
+    /// and is u32::MAX, e.g. a measurement that saturated.
+    #[inline]
+    pub fn see_reporting_saturation(&mut self, value: u32) -> bool {
+        self.rank(value) != 0 && value == u32::MAX
+    }
+
+    /// Reads little-endian u32s from the reader until it reaches the end, and sees each
+    /// of them, as see() does.  It returns the number of values read.
+    /// If the stream ends partway through a value, the values before it have still
+    /// been seen, and it returns an error of kind UnexpectedEof.
+    /// It reads in blocks, so there is no need to wrap the reader in a BufReader.
+    #[cfg(feature = "std")]
+    pub fn see_from_reader<R: std::io::Read>(&mut self, r: &mut R) -> std::io::Result<u64> {
+        use std::io::{Error, ErrorKind};
+        let mut buf = [0u8; 4096];
+        let mut len = 0; // The number of bytes in buf.
+        let mut count = 0;
+        loop {
+            match r.read(&mut buf[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+            let chunks = buf[..len].chunks_exact(4);
+            let whole = len - chunks.remainder().len();
+            count += chunks.len() as u64;
+            self.see_all(chunks.map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])));
+            // Keep any partial value for the next read.
+            buf.copy_within(whole..len, 0);
+            len -= whole;
+        }
+        if len != 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "the stream ended partway through a u32",
+            ));
+        }
+        Ok(count)
+    }
+
+    /// Returns an Iterator over the differences between consecutive retained values,
+    /// in ascending order of the values, so the first is the gap between the smallest two.
+    /// It is empty if fewer than two values are retained.
+    pub fn gaps(&self) -> impl Iterator<Item = u32> + '_ {
+        let asc = self.iter().rev();
+        asc.clone()
+            .zip(asc.skip(1))
+            .map(|(lower, upper)| upper - lower)
+    }
+
+    /// Estimates the q quantile of a stream of total_seen values larger than the cutoff,
+    /// of which this Top16 has seen all, e.g. q = 0.99 for the 99th percentile.
+    /// It returns the value at position ceil(q * total_seen) when all of the values are
+    /// sorted in ascending order, which is exact, not an estimate, if that is one of
+    /// the retained values.  Since only the top 16 are retained, that is only the case
+    /// in the extreme top tail, where q * total_seen is within 16 of total_seen;
+    /// e.g. for a million values, q = 0.99999 maps to the 11th largest value,
+    /// but q = 0.999 would need the top 1000, so for that it returns None.
+    /// It also returns None if q is not between 0 and 1, or if total_seen is 0.
+    pub fn approx_quantile(&self, q: f64, total_seen: u64) -> Option<u32> {
+        if !(0.0..=1.0).contains(&q) || total_seen == 0 {
+            return None;
+        }
+        // Rounding up by hand, since f64::ceil() needs std.
+        let x = q * total_seen as f64;
+        let mut pos = x as u64;
+        if (pos as f64) < x {
+            pos += 1;
+        }
+        // Positions are 1-based, and q = 0 means the smallest.
+        let from_top = total_seen - pos.max(1);
+        self.get(usize::try_from(from_top).ok()?)
+    }
+
+    /// Returns the p-th percentile of the retained values, for p from 0 to 100,
+    /// interpolating linearly between the two values on either side of it and
+    /// rounding down, so 0 gives the smallest retained value and 100 the largest.
+    /// This is only about the retained values, not the stream; see approx_quantile().
+    /// It returns None if no values are retained or p is not between 0 and 100.
+    pub fn percentile(&self, p: f64) -> Option<u32> {
+        if !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let last = self.len().checked_sub(1)?;
+        // The position in ascending order, and how far it is toward the next one.
+        let pos = p / 100.0 * last as f64;
+        let below = pos as usize;
+        let lower = self.get(last - below)?;
+        if below == last {
+            return Some(lower);
+        }
+        let upper = self.get(last - below - 1)?;
+        Some(lower + ((upper - lower) as f64 * (pos - below as f64)) as u32)
+    }
+
+    /// Returns the number of retained values in each of the specified number of
+    /// equal-width buckets spanning the range() of the retained values.
+    /// The last bucket includes the largest value, so if all of the values are equal,
+    /// they all go in the first bucket.  If none are retained, all of the counts are 0.
+    #[cfg(feature = "alloc")]
+    pub fn histogram(&self, buckets: usize) -> Vec<usize> {
+        let mut counts = alloc::vec![0; buckets];
+        if let (Some((min, max)), Some(last)) = (self.range(), buckets.checked_sub(1)) {
+            let span = (max - min).max(1) as u128;
+            for v in self {
+                let bucket = (v - min) as u128 * buckets as u128 / span;
+                counts[(bucket as usize).min(last)] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns the sum of the retained values.  It is a u64, so it cannot overflow.
+    pub fn sum(&self) -> u64 {
+        self.iter().map(u64::from).sum()
+    }
+
+    /// Returns the sum of the retained values as a u32, or None if it does not fit.
+    pub fn checked_sum(&self) -> Option<u32> {
+        self.iter().try_fold(0u32, u32::checked_add)
+    }
+
+    /// Returns a one-line description of the cutoff and the retained values,
+    /// e.g. `cutoff=0;values=20,19,18`, for pasting into a bug report.
+    /// from_debug_string() turns it back into a TopFew.
+    #[cfg(feature = "alloc")]
+    pub fn to_debug_string(&self) -> String {
+        let values: Vec<String> = self.iter().map(|v| format!("{v}")).collect();
+        format!("cutoff={};values={}", self.cutoff, values.join(","))
+    }
+
+    /// Parses a string from to_debug_string(), which may have been edited,
+    /// and returns a new TopFew with that cutoff that has seen those values in order.
+    /// So values not above the cutoff are ignored, and only the top 16 are kept.
+    /// Spaces around the numbers are allowed.
+    pub fn from_debug_string(s: &str) -> Result<Self, ParseError> {
+        let (cutoff, values) = s
+            .trim()
+            .strip_prefix("cutoff=")
+            .and_then(|rest| rest.split_once(";values="))
+            .ok_or(ParseError::Format)?;
+        let mut top = Self::new(cutoff.trim().parse().map_err(ParseError::Value)?);
+        if !values.trim().is_empty() {
+            for v in values.split(',') {
+                top.see(v.trim().parse().map_err(ParseError::Value)?);
+            }
+        }
+        Ok(top)
+    }
+}
+
+/// The default TopFew has a cutoff of T::default(), e.g. 0 for Top16,
+/// so a Top16 considers all positive values.
+impl<T: Ord + Copy + Default, const N: usize, O: Order> Default for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Builds a TopFew with a cutoff of 0 that has seen each of the values in turn.
+///
+/// ```
+/// use top_few::Top16;
+///
+/// let t = Top16::from(&[3, 1, 4, 1, 5][..]);
+/// assert!(t.iter().eq([5, 4, 3, 1, 1]));
+/// ```
+impl<const N: usize> From<&[u32]> for TopFew<u32, N>
+where
+    Cap<N>: Capacity,
+{
+    fn from(values: &[u32]) -> Self {
+        let mut top = Self::new(0);
+        top.see_all(values.iter().copied());
+        top
+    }
+}
+
+/// Two TopFews are equal if they have the same cutoff and hold the same values.
+/// Equality is about the resulting multiset of values, not the internal
+/// representation, so two instances that saw the same values in different orders
+/// are equal even though their elements may be stored in different slots.
+impl<T: Ord + Copy, const N: usize, O: Order> PartialEq for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // The iterators return the values in sorted order, so equal multisets
+        // produce equal sequences.
+        self.cutoff == other.cutoff && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> Eq for TopFew<T, N, O> where Cap<N>: Capacity {}
+
+/// Hashes the cutoff and the retained values, consistent with PartialEq.
+impl<T: Ord + Copy + Hash, const N: usize, O: Order> Hash for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cutoff.hash(state);
+        for v in self.iter() {
+            v.hash(state);
+        }
+    }
+}
+
+/// Shows just the retained values, in descending order, e.g. `[20, 19, 18]`.
+/// Use Debug to see the internal representation.
+impl<T: Ord + Copy + Display, const N: usize, O: Order> Display for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "[")?;
+        for (i, v) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{v}")?;
+        }
+        write!(f, "]")
+    }
 }
 
 // Custom Debug implementation to show sorted_ixs as hex.
-impl Debug for Top16 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: Debug, const N: usize, O: Order> Debug for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
-            "Top16 {{ cutoff: {}, threshold: {}, sorted_ixs: {:016X}, elements: [",
-            self.cutoff, self.threshold, self.sorted_ixs
+            "TopFew {{ cutoff: {:?}, threshold: {:?}, sorted_ixs: {:0w$X}, elements: [",
+            self.cutoff,
+            self.threshold,
+            self.sorted_ixs.to_u128(),
+            w = Self::IXS_BITS as usize / 4
         )?;
-        for (i, &v) in self.elements.iter().enumerate() {
+        for (i, v) in self.elements.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
                 if i % 4 == 0 {
@@ -251,44 +1623,335 @@ impl Debug for Top16 {
             }
             write!(f, "{v:?}")?;
         }
-        write!(f, "]}}")
+        write!(f, "]")?;
+        #[cfg(feature = "counters")]
+        write!(f, ", max_ever: {:?}", self.max_ever)?;
+        write!(f, "}}")
+    }
+}
+
+/// The outcome of an insert() call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsertResult<T = u32> {
+    /// The position of the value, as returned by rank(): 0 if it was not admitted,
+    /// or 1 for the smallest retained value through 16 for the largest.
+    pub rank: usize,
+    /// The value that was evicted to make room, if any.
+    /// It is always None if the rank is 0.
+    pub evicted: Option<T>,
+}
+
+/// The reason that from_debug_string() could not parse its input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input was not of the form `cutoff=...;values=...`.
+    Format,
+    /// The cutoff or one of the values was not a valid u32.
+    Value(core::num::ParseIntError),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Format => write!(f, "expected cutoff=...;values=..."),
+            Self::Value(e) => write!(f, "invalid number: {e}"),
+        }
     }
 }
 
-/// Iterator for a Top16.  It returns the top 16 elements in descending order.
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Format => None,
+            Self::Value(e) => Some(e),
+        }
+    }
+}
+
+/// What happened to a value that classify() considered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SeeOutcome<T = u32> {
+    /// The value did not get in.
+    Rejected,
+    /// The value got in, and nothing was evicted, since the TopFew had not filled up.
+    AddedNotFull {
+        /// The position of the value, as returned by rank().
+        position: usize,
+    },
+    /// The value got in, and the smallest value was evicted to make room.
+    Admitted {
+        /// The position of the value, as returned by rank().
+        position: usize,
+        /// The value that was evicted.
+        evicted: T,
+    },
+}
+
+impl<T: Copy> SeeOutcome<T> {
+    /// Returns the position of the value, as returned by rank(): 0 if it was rejected,
+    /// or 1 for the smallest retained value through 16 for the largest.
+    #[inline]
+    pub fn position(&self) -> usize {
+        match *self {
+            Self::Rejected => 0,
+            Self::AddedNotFull { position } | Self::Admitted { position, .. } => position,
+        }
+    }
+
+    /// Returns the value that was evicted, if any.
+    #[inline]
+    pub fn evicted(&self) -> Option<T> {
+        match *self {
+            Self::Admitted { evicted, .. } => Some(evicted),
+            _ => None,
+        }
+    }
+}
+
+/// The state that a see_undoable() call changed, for undo() to put back.
+#[derive(Clone, Copy, Debug)]
+pub struct UndoToken<T = u32, const N: usize = 16>
+where
+    Cap<N>: Capacity,
+{
+    // The sorted indices before the value was seen.
+    sorted_ixs: Store<N>,
+    // The slot that an admitted value went in, and what was in it before.
+    slot: usize,
+    evicted: T,
+    // The threshold before the value was seen.
+    threshold: T,
+}
+
+/// A TopFew borrowed by scoped(), which it clears when it is dropped.
+/// It derefs to the TopFew, so you can call see() etc. on it directly.
+pub struct ScopeGuard<'a, T: Ord + Copy, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
+    top: &'a mut TopFew<T, N, O>,
+    // The cutoff to reinit() with.
+    cutoff: T,
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> Deref for ScopeGuard<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Target = TopFew<T, N, O>;
+
+    fn deref(&self) -> &Self::Target {
+        self.top
+    }
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> DerefMut for ScopeGuard<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.top
+    }
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> Drop for ScopeGuard<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn drop(&mut self) {
+        self.top.reinit(self.cutoff);
+    }
+}
+
+/// Iterator for a TopFew, e.g. a Top16.  It returns the top 16 elements in descending order.
 /// The iterator is double-ended, so you can use .rev() to get ascending order.
 /// Note that the iterator will only return values larger than the cutoff value.
 /// If the Top16 has not seen 16 values larger than the cutoff, the Iterator will
 /// return less than 16 values.
-pub struct Iter<'a> {
-    // The Top16 instance to iterate over.
-    top: &'a crate::Top16,
+pub struct Iter<'a, T = u32, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
+    // The TopFew instance to iterate over.
+    top: &'a TopFew<T, N, O>,
     // The bit position of the next element to return for next_back().
     fwd_shift: u32,
     // The bit position just past the next element to return for next().
     bwd_shift: u32,
 }
 
-impl Iterator for Iter<'_> {
-    type Item = u32;
+impl<T: Ord + Copy, const N: usize, O: Order> Iterator for Iter<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
         if self.fwd_shift == self.bwd_shift {
             None
         } else {
-            self.bwd_shift -= IX_BITS;
+            self.bwd_shift -= TopFew::<T, N, O>::IX_BITS;
             Some(self.top.element_at(self.bwd_shift))
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = ((self.bwd_shift - self.fwd_shift) / TopFew::<T, N, O>::IX_BITS) as usize;
+        (n, Some(n))
+    }
+
+    // Skips directly to the nth element rather than stepping through them.
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.bwd_shift = self.fwd_shift;
+            None
+        } else {
+            self.bwd_shift -= n as u32 * TopFew::<T, N, O>::IX_BITS;
+            self.next()
+        }
+    }
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> ExactSizeIterator for Iter<'_, T, N, O> where
+    Cap<N>: Capacity
+{
+}
+
+/// Cloning an Iter is cheap, since it only holds a reference and two positions,
+/// and the clone continues from the same place.
+impl<T, const N: usize, O: Order> Clone for Iter<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn clone(&self) -> Self {
+        Self {
+            top: self.top,
+            fwd_shift: self.fwd_shift,
+            bwd_shift: self.bwd_shift,
+        }
+    }
+}
+
+/// Once an Iter returns None, it keeps returning None.
+///
+/// ```
+/// use top_few::Top16;
+///
+/// let mut top = Top16::new(0);
+/// top.see(7);
+/// let mut iter = top.iter();
+/// assert_eq!(iter.next(), Some(7));
+/// assert_eq!(iter.next(), None);
+/// assert_eq!(iter.next(), None);
+/// ```
+impl<T: Ord + Copy, const N: usize, O: Order> FusedIterator for Iter<'_, T, N, O> where
+    Cap<N>: Capacity
+{
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> DoubleEndedIterator for Iter<'_, T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.fwd_shift == self.bwd_shift {
+            None
+        } else {
+            let ix = self.top.ix(self.fwd_shift);
+            self.fwd_shift += TopFew::<T, N, O>::IX_BITS;
+            Some(self.top.elements[ix])
+        }
+    }
+}
+
+impl<'a, T: Ord + Copy, const N: usize, O: Order> IntoIterator for &'a TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T, N, O>;
+    fn into_iter(self) -> Iter<'a, T, N, O> {
+        self.iter()
+    }
+}
+
+/// Owning iterator for a TopFew, which returns the same values as Iter
+/// but without borrowing the TopFew.  Since a TopFew is Copy, this is cheap.
+pub struct IntoIter<T = u32, const N: usize = 16, O: Order = Max>
+where
+    Cap<N>: Capacity,
+{
+    // The TopFew instance to iterate over.
+    top: TopFew<T, N, O>,
+    // The bit position of the next element to return for next_back().
+    fwd_shift: u32,
+    // The bit position just past the next element to return for next().
+    bwd_shift: u32,
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> IntoIterator for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, N, O>;
+    fn into_iter(self) -> IntoIter<T, N, O> {
+        let Iter {
+            fwd_shift,
+            bwd_shift,
+            ..
+        } = self.iter();
+        IntoIter {
+            top: self,
+            fwd_shift,
+            bwd_shift,
+        }
+    }
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> Iterator for IntoIter<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fwd_shift == self.bwd_shift {
+            None
+        } else {
+            self.bwd_shift -= TopFew::<T, N, O>::IX_BITS;
+            Some(self.top.element_at(self.bwd_shift))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = ((self.bwd_shift - self.fwd_shift) / TopFew::<T, N, O>::IX_BITS) as usize;
+        (n, Some(n))
+    }
 }
 
-impl DoubleEndedIterator for Iter<'_> {
+impl<T: Ord + Copy, const N: usize, O: Order> DoubleEndedIterator for IntoIter<T, N, O>
+where
+    Cap<N>: Capacity,
+{
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.fwd_shift == self.bwd_shift {
             None
         } else {
             let ix = self.top.ix(self.fwd_shift);
-            self.fwd_shift += IX_BITS;
+            self.fwd_shift += TopFew::<T, N, O>::IX_BITS;
             Some(self.top.elements[ix])
         }
     }
 }
+
+impl<T: Ord + Copy, const N: usize, O: Order> ExactSizeIterator for IntoIter<T, N, O> where
+    Cap<N>: Capacity
+{
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> FusedIterator for IntoIter<T, N, O> where
+    Cap<N>: Capacity
+{
+}
@@ -0,0 +1,110 @@
+//! TopK is what the accumulators in this crate have in common, so that code
+//! can accept any of them, e.g. a Top1, a Top8 or a Top16, and leave the choice
+//! of how many values to keep to its caller.
+//!
+//! The iterator is an associated type, so iterating over the values of a TopFew
+//! through the trait is just as fast as calling its own iter().
+//! The trait methods just call the inherent methods of the same names,
+//! so see the documentation of those for the details.
+
+use core::option;
+
+use crate::ixs::{Cap, Capacity};
+use crate::order::Order;
+use crate::top1::Top1;
+use crate::top16::Iter;
+use crate::top16::TopFew;
+
+/// An accumulator of the top values seen.
+pub trait TopK {
+    /// The type of the values.
+    type Value;
+
+    /// Considers a new value, keeping it if it is one of the top ones.
+    fn see(&mut self, value: Self::Value);
+
+    /// Returns the largest retained value, or None if there are none.
+    fn max(&self) -> Option<Self::Value>;
+
+    /// Changes the cutoff, removing any retained values that are not larger than it.
+    fn set_cutoff(&mut self, new_cutoff: Self::Value);
+
+    /// The type of iterator that iter() returns.
+    type Iter<'a>: Iterator<Item = Self::Value>
+    where
+        Self: 'a;
+
+    /// Returns an Iterator over the retained values, in descending order.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Returns the number of values that it can retain, e.g. 16 for a Top16.
+    fn capacity(&self) -> usize;
+}
+
+impl<T: Ord + Copy, const N: usize, O: Order> TopK for TopFew<T, N, O>
+where
+    Cap<N>: Capacity,
+{
+    type Value = T;
+
+    #[inline]
+    fn see(&mut self, value: T) {
+        TopFew::see(self, value);
+    }
+
+    #[inline]
+    fn max(&self) -> Option<T> {
+        TopFew::max(self)
+    }
+
+    #[inline]
+    fn set_cutoff(&mut self, new_cutoff: T) {
+        TopFew::set_cutoff(self, new_cutoff);
+    }
+
+    type Iter<'a>
+        = Iter<'a, T, N, O>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn iter(&self) -> Iter<'_, T, N, O> {
+        TopFew::iter(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        TopFew::capacity(self)
+    }
+}
+
+impl TopK for Top1 {
+    type Value = u32;
+
+    #[inline]
+    fn see(&mut self, value: u32) {
+        Top1::see(self, value);
+    }
+
+    #[inline]
+    fn max(&self) -> Option<u32> {
+        Top1::max(self)
+    }
+
+    #[inline]
+    fn set_cutoff(&mut self, new_cutoff: u32) {
+        Top1::set_cutoff(self, new_cutoff);
+    }
+
+    type Iter<'a> = option::IntoIter<u32>;
+
+    #[inline]
+    fn iter(&self) -> option::IntoIter<u32> {
+        Top1::iter(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        1
+    }
+}
@@ -0,0 +1,120 @@
+//! TopFewUnbounded keeps track of the top 16 of all of the values seen,
+//! including the smallest value of the type, e.g. 0 for a u32, which a Top16
+//! can never retain, since its cutoff is exclusive.
+//!
+//! The other values go in a TopFew with that smallest value as its cutoff, as usual.
+//! Any instances of the smallest value would be the smallest retained values, so all
+//! we need to know about them is how many there are: the ones that fit in the slots
+//! that the other values have not filled.  Since seeing a value never reduces
+//! the number of other values retained, we just count the instances of the
+//! smallest value seen, up to 16, and take as many of them as fit.
+
+use core::iter;
+
+use crate::TopFew;
+
+/// A type with a smallest value, which a TopFewUnbounded of it uses as the cutoff.
+/// It is implemented for the primitive integer types.
+pub trait MinValue: Ord + Copy {
+    /// The smallest value, e.g. 0 for a u32 or i32::MIN for an i32.
+    const MIN: Self;
+}
+
+macro_rules! impl_min_value {
+    ($($t:ty),*) => {
+        $(
+            impl MinValue for $t {
+                const MIN: Self = <$t>::MIN;
+            }
+        )*
+    };
+}
+
+impl_min_value!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// The top 16 values seen, which can include the smallest value of the type.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewUnbounded<T = u32> {
+    // The top values other than T::MIN.
+    top: TopFew<T>,
+    // The number of instances of T::MIN seen, up to 16.
+    mins: usize,
+}
+
+impl<T: MinValue> TopFewUnbounded<T> {
+    /// Returns a new instance of TopFewUnbounded, which considers every value.
+    pub fn new() -> Self {
+        Self {
+            top: TopFew::new(T::MIN),
+            mins: 0,
+        }
+    }
+
+    /// Returns the number of retained values, which is 16 once 16 values have been seen.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let others = self.top.len();
+        others + self.mins.min(16 - others)
+    }
+
+    /// Returns true if no values have been seen.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.top.is_empty() && self.mins == 0
+    }
+
+    /// Returns the largest retained value, or None if no values have been seen.
+    #[inline]
+    pub fn max(&self) -> Option<T> {
+        self.top.max().or((self.mins > 0).then_some(T::MIN))
+    }
+
+    /// Considers a new value, as Top16::rank() does, and returns its position:
+    /// 0 if it is not in the top 16, or 1 for the smallest position through 16
+    /// for the largest.  As usual, older values win ties.
+    pub fn rank(&mut self, value: T) -> usize {
+        if value != T::MIN {
+            return self.top.rank(value);
+        }
+        // The new one goes below the other values and the instances of T::MIN
+        // already retained, if there is room for it.
+        let filled = self.len();
+        if filled == 16 {
+            return 0;
+        }
+        self.mins += 1;
+        16 - filled
+    }
+
+    /// Considers a new value to see if it is one of the top 16.
+    /// If so, it is added to the list.
+    #[inline]
+    pub fn see(&mut self, value: T) {
+        self.rank(value);
+    }
+
+    /// Returns an Iterator over the top 16 values (or less if fewer were seen),
+    /// in descending order, ending with any instances of T::MIN.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = T> + '_ {
+        let others = self.top.len();
+        self.top
+            .iter()
+            .chain(iter::repeat_n(T::MIN, self.mins.min(16 - others)))
+    }
+}
+
+impl<T: MinValue> Default for TopFewUnbounded<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: MinValue> TopFew<T> {
+    /// Returns a new TopFewUnbounded, which is like a TopFew that considers every value,
+    /// including the smallest value of the type, which no cutoff would let in.
+    pub fn unbounded() -> TopFewUnbounded<T> {
+        TopFewUnbounded::new()
+    }
+}
@@ -0,0 +1,63 @@
+//! TopFewWeighted keeps track of the top 16 values seen so far, each with a weight,
+//! along with the total weight of the values retained.
+//!
+//! As with TopFewBy, the values are kept in a Top16 and the weights in an array
+//! parallel to its elements, so when a value takes over the smallest value's slot,
+//! the evicted value's weight is subtracted from the total before the new one is added.
+//! An unfilled slot has a weight of 0, so filling it needs no special case.
+//! That keeps total_weight() O(1), e.g. for the fraction of all the weight seen
+//! that the top 16 values account for.
+
+use crate::Top16;
+
+/// The top 16 values seen, with a weight for each and the total of their weights.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewWeighted {
+    // The top values.
+    top: Top16,
+    // The weight of the value in each of the top's slots, or 0 if it is unfilled.
+    weights: [u32; 16],
+    // The sum of the weights.
+    total: u64,
+}
+
+impl TopFewWeighted {
+    /// Returns a new instance of TopFewWeighted.
+    /// Only values larger than the cutoff will be considered.
+    pub fn new(cutoff: u32) -> Self {
+        Self {
+            top: Top16::new(cutoff),
+            weights: [0; 16],
+            total: 0,
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> u32 {
+        self.top.cutoff()
+    }
+
+    /// Returns the sum of the weights of the retained values.
+    #[inline]
+    pub fn total_weight(&self) -> u64 {
+        self.total
+    }
+
+    /// Considers a new value to see if it is one of the top 16.
+    /// If so, it is added to the list along with its weight,
+    /// and the weight of any value it pushes out is dropped from the total.
+    pub fn see(&mut self, value: u32, weight: u32) {
+        if let Some(slot) = self.top.see_slot(value) {
+            self.total -= u64::from(self.weights[slot]);
+            self.total += u64::from(weight);
+            self.weights[slot] = weight;
+        }
+    }
+
+    /// Returns an Iterator over the (value, weight) pairs of the top 16 values
+    /// (or less if fewer were seen), in descending order of value.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u32, u32)> + '_ {
+        self.top.slots().map(|(slot, v)| (v, self.weights[slot]))
+    }
+}
@@ -0,0 +1,106 @@
+//! TopFewWindow keeps track of the top 16 values among the most recent values seen,
+//! i.e. within a sliding window over the stream, rather than over the whole stream.
+//!
+//! Each retained value is stamped with its sequence number in the stream,
+//! and values that fall out of the window are removed before each new value is seen,
+//! which makes room for newer values.
+//!
+//! Note that this is an approximation of the true top 16 in the window.
+//! A recent value that was not retained because larger but older values were,
+//! or that was pushed out by them, does not come back when they expire.
+//! What you get is the values in the window that are still in the top 16,
+//! which is exactly right for the largest values and may be missing some smaller ones.
+
+use crate::Top16;
+
+/// The top 16 values among the most recent values seen.
+#[derive(Clone, Copy, Debug)]
+pub struct TopFewWindow {
+    // The top values.
+    top: Top16,
+    // The sequence number of the value in each of the top's slots.
+    seqs: [u64; 16],
+    // No larger than the smallest retained sequence number.  So if it is in the window,
+    // they all are, and if it is not, then we should check for expired values.
+    oldest: u64,
+    // The number of values to keep values for.
+    window: u64,
+    // The sequence number for the next value seen.
+    current_seq: u64,
+}
+
+impl TopFewWindow {
+    /// Returns a new instance of TopFewWindow which considers only the most recent
+    /// window values.  Only values larger than the cutoff will be considered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window is 0, since the value just seen is always in the window.
+    pub fn new(cutoff: u32, window: u64) -> Self {
+        assert!(
+            window > 0,
+            "a window of 0 values would never retain anything"
+        );
+        Self {
+            top: Top16::new(cutoff),
+            seqs: [0; 16],
+            oldest: u64::MAX,
+            window,
+            current_seq: 0,
+        }
+    }
+
+    /// Returns the current cutoff value.
+    #[inline]
+    pub fn cutoff(&self) -> u32 {
+        self.top.cutoff()
+    }
+
+    /// Returns the size of the window.
+    #[inline]
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Considers a new value to see if is one of the top 16 in the window.
+    /// If so, it is added to the list.  Values that fall out of the window are removed.
+    pub fn see(&mut self, value: u32) {
+        let seq = self.current_seq;
+        self.current_seq += 1;
+        if self.expired(self.oldest) {
+            self.expire();
+        }
+        if let Some(slot) = self.top.see_slot(value) {
+            self.seqs[slot] = seq;
+            self.oldest = self.oldest.min(seq);
+        }
+    }
+
+    /// Returns an Iterator over the top 16 elements in the window (or less if there are less),
+    /// in descending order.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u32> + '_ {
+        self.top.iter()
+    }
+
+    // Returns true if the specified sequence number is no longer in the window.
+    #[inline]
+    fn expired(&self, seq: u64) -> bool {
+        seq.saturating_add(self.window) < self.current_seq
+    }
+
+    // Removes the expired values, and recomputes oldest.
+    fn expire(&mut self) {
+        let seqs = self.seqs;
+        let current_seq = self.current_seq;
+        let window = self.window;
+        self.top
+            .retain_slots(|slot, _| seqs[slot].saturating_add(window) >= current_seq);
+        self.oldest = self
+            .top
+            .slots()
+            .map(|(slot, _)| self.seqs[slot])
+            .min()
+            .unwrap_or(u64::MAX);
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+use proptest::prelude::*;
+use top_few::{Min, TopFewBand};
+
+#[test]
+fn band() {
+    let mut it = TopFewBand::<u32, 4>::new(10, 20);
+    assert_eq!(it.rank(10), 0);
+    assert_eq!(it.rank(20), 0);
+    assert_eq!(it.rank(25), 0);
+    assert_eq!(it.rank(11), 4);
+    assert_eq!(it.rank(19), 4);
+    assert_eq!(it.see_all([5, 30, 20, 15, 12, 18]), 3);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [19, 18, 15, 12]);
+    assert_eq!(it.check_invariants(), Ok(()));
+    assert!(!it.would_admit(20));
+    assert!(it.would_admit(13));
+    it.reinit(15);
+    assert_eq!((it.cutoff(), it.ceiling()), (15, 20));
+    it.see_all([14, 16, 20, 21]);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [16]);
+}
+
+#[test]
+fn next_admission_value() {
+    let mut it = TopFewBand::<u32, 2>::new(10, 20);
+    assert_eq!(it.next_admission_value(), Some(11));
+    it.see_all([15, 18]);
+    assert_eq!(it.next_admission_value(), Some(16));
+    // Once the smallest retained value is just below the ceiling, nothing can get in.
+    it.see_all([19, 19]);
+    assert_eq!(it.next_admission_value(), None);
+}
+
+#[test]
+fn min_order() {
+    // For Min, the band is the values below the cutoff and above the ceiling.
+    let mut it = TopFewBand::<u32, 2, Min>::new(50, 10);
+    it.see_all([5, 10, 60, 30, 11, 12]);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [11, 12]);
+    assert_eq!(it.check_invariants(), Ok(()));
+}
+
+proptest! {
+    #[test]
+    fn proptest_band(
+        a in prop::collection::vec(0..64u32, 0..100),
+        b in prop::collection::vec(0..64u32, 0..100),
+    ) {
+        let mut x = TopFewBand::<u32>::new(10, 50);
+        x.see_all(a.iter().copied());
+        let mut expected: Vec<u32> = a.iter().copied().filter(|&v| v > 10 && v < 50).collect();
+        expected.sort_unstable_by(|p, q| q.cmp(p));
+        expected.truncate(16);
+        prop_assert_eq!(x.iter().collect::<Vec<_>>(), expected);
+
+        // Merging brings in only the other's values that are in the band.
+        let mut y = TopFewBand::<u32>::new(0, 50);
+        y.see_all(b.iter().copied());
+        let mut merged = x;
+        merged.merge(y.as_top_few());
+        prop_assert_eq!(merged.check_invariants(), Ok(()));
+        let mut all = TopFewBand::<u32>::new(10, 50);
+        all.see_all(a.iter().chain(&b).copied());
+        prop_assert_eq!(merged.as_top_few(), all.as_top_few());
+    }
+}
@@ -0,0 +1,46 @@
+use proptest::prelude::*;
+use top_few::{BottomFew, Top16};
+
+#[test]
+fn ascending() {
+    let mut it = BottomFew::new(100);
+    for i in (1..200).rev() {
+        it.see(i);
+    }
+    let elements: Vec<u32> = it.iter().collect();
+    let expected: Vec<u32> = (1..17).collect();
+    assert_eq!(elements, expected);
+    assert_eq!(it.min(), Some(1));
+
+    // Lower the cutoff to drop some values.
+    it.set_cutoff(10);
+    let elements: Vec<u32> = it.iter().rev().collect();
+    let expected: Vec<u32> = (1..10).rev().collect();
+    assert_eq!(elements, expected);
+    assert_eq!(it.cutoff(), 10);
+}
+
+#[test]
+fn ranks() {
+    let mut it = BottomFew::new(10);
+    assert_eq!(it.rank(10), 0);
+    assert_eq!(it.rank(5), 16);
+    assert_eq!(it.rank(7), 15);
+    assert_eq!(it.rank(6), 15);
+    assert_eq!(it.rank(1), 16);
+}
+
+proptest! {
+    #[test]
+    fn proptest_mirrors_top16_on_complements(data in prop::collection::vec(any::<u32>(), 1..1000)) {
+        let mut bottom = BottomFew::new(u32::MAX);
+        let mut top = Top16::new(0);
+        for &x in &data {
+            bottom.see(x);
+            top.see(!x);
+        }
+        let bottom_values: Vec<u32> = bottom.iter().collect();
+        let top_values: Vec<u32> = top.iter().map(|v| !v).collect();
+        assert_eq!(bottom_values, top_values);
+    }
+}
@@ -0,0 +1,49 @@
+use top_few::TopFewBy;
+
+#[derive(Debug, PartialEq)]
+struct Job {
+    name: String,
+    millis: u32,
+}
+
+fn job(i: u32) -> Job {
+    Job {
+        name: format!("job{i}"),
+        millis: i * 37 % 101,
+    }
+}
+
+#[test]
+fn top_jobs() {
+    let mut it = TopFewBy::new(0, |j: &Job| j.millis);
+    assert_eq!(it.max(), None);
+    for i in 0..100 {
+        it.see(job(i));
+    }
+    let mut expected: Vec<Job> = (0..100).map(job).filter(|j| j.millis > 0).collect();
+    expected.sort_by_key(|j| std::cmp::Reverse(j.millis));
+    expected.truncate(16);
+    assert!(it.iter().eq(expected.iter()));
+    assert_eq!(it.max(), expected.first());
+    let keys: Vec<u32> = it.iter_keyed().map(|(k, _)| k).collect();
+    assert_eq!(keys, expected.iter().map(|j| j.millis).collect::<Vec<_>>());
+}
+
+#[test]
+fn cutoff_and_ties() {
+    fn len(s: &&str) -> usize {
+        s.len()
+    }
+    let mut it: TopFewBy<&str, usize> = TopFewBy::new(1, len);
+    assert_eq!(it.cutoff(), 1);
+    assert!(!it.see("a"));
+    assert!(it.see("bb"));
+    for _ in 0..15 {
+        assert!(it.see("ccc"));
+    }
+    // "dd" only ties "bb", so it does not get in, but "eeee" does.
+    assert!(!it.see("dd"));
+    assert!(it.see("eeee"));
+    let items: Vec<&str> = it.iter().rev().copied().collect();
+    assert_eq!(items, [["ccc"; 15].as_slice(), &["eeee"]].concat());
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "alloc")]
+
+use top_few::Top16;
+use top_few::columns::top16_columns;
+
+#[test]
+fn one_top16_per_column() {
+    let cols = 3;
+    let data: Vec<u32> = (0..100).map(|i| i * 31 % 89).collect();
+    let tops = top16_columns(&data, cols, 5);
+    assert_eq!(tops.len(), cols);
+    for (c, top) in tops.iter().enumerate() {
+        let mut expected = Top16::new(5);
+        expected.see_all(data.iter().copied().skip(c).step_by(cols));
+        assert_eq!(*top, expected);
+    }
+}
+
+#[test]
+fn no_rows() {
+    assert_eq!(top16_columns(&[], 4, 0), [Top16::new(0); 4]);
+}
+
+#[test]
+#[should_panic]
+fn no_columns() {
+    top16_columns(&[1, 2], 0, 0);
+}
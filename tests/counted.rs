@@ -0,0 +1,78 @@
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+use top_few::TopFewCounted;
+
+#[test]
+fn counts_repeats() {
+    let mut it = TopFewCounted::new(0);
+    for i in 1..=20 {
+        for _ in 0..i % 3 + 1 {
+            it.see(i);
+        }
+    }
+    let pairs: Vec<(u32, u32)> = it.iter().collect();
+    let expected: Vec<(u32, u32)> = (5..=20).rev().map(|i| (i, i % 3 + 1)).collect();
+    assert_eq!(pairs, expected);
+    assert_eq!(
+        it.total_count(),
+        (5..=20u64).map(|i| i % 3 + 1).sum::<u64>()
+    );
+}
+
+#[test]
+fn evicted_counts_start_over() {
+    let mut it = TopFewCounted::new(0);
+    it.see(1);
+    it.see(1);
+    for i in 2..=16 {
+        it.see(i);
+    }
+    assert_eq!(it.total_count(), 17);
+    it.see(17); // Pushes out the 1s.
+    assert_eq!(it.total_count(), 16);
+    assert_eq!(it.iter().last(), Some((2, 1)));
+    it.see(1); // Too small to get back in.
+    assert_eq!(it.iter().last(), Some((2, 1)));
+    assert_eq!(it.total_count(), 16);
+    it.see(2);
+    assert_eq!(it.iter().last(), Some((2, 2)));
+    assert_eq!(it.total_count(), 17);
+}
+
+#[test]
+fn repeats_keep_their_rank_by_value() {
+    let mut it = TopFewCounted::new(0);
+    for _ in 0..100 {
+        it.see(1);
+    }
+    for i in 2..=17 {
+        it.see(i);
+    }
+    // The 1 was seen the most, but it is still the smallest value, so it is gone.
+    assert_eq!(it.iter().last(), Some((2, 1)));
+    assert_eq!(it.total_count(), 16);
+    for _ in 0..5 {
+        it.see(10);
+    }
+    assert_eq!(it.iter().nth(7), Some((10, 6)));
+    assert_eq!(it.total_count(), 21);
+}
+
+proptest! {
+    #[test]
+    fn total_count_matches_retained(values in prop::collection::vec(0u32..40, 0..200)) {
+        let mut it = TopFewCounted::new(0);
+        for &v in &values {
+            it.see(v);
+        }
+        let total: u64 = it.iter().map(|(_, c)| u64::from(c)).sum();
+        prop_assert_eq!(it.total_count(), total);
+        // The values are the top distinct ones.
+        let mut distinct = BTreeSet::new();
+        for &v in values.iter().filter(|&&v| v > 0) {
+            distinct.insert(v);
+        }
+        let expected: Vec<u32> = distinct.iter().rev().take(16).copied().collect();
+        prop_assert_eq!(it.iter().map(|(v, _)| v).collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+
+use proptest::prelude::*;
+use top_few::{Min, TopFewDistinct};
+
+#[test]
+fn distinct_values_only() {
+    let mut it = TopFewDistinct::<u32>::new(0);
+    let data: Vec<u32> = (0..1000).map(|i| i * 7 % 40).collect();
+    for &v in &data {
+        it.see(v);
+        assert_eq!(it.check_invariants(), Ok(()));
+    }
+    assert_eq!(
+        it.iter().collect::<Vec<_>>(),
+        (24..40).rev().collect::<Vec<_>>()
+    );
+    // A duplicate does not evict anything.
+    assert_eq!(it.rank(30), 0);
+    assert_eq!(it.rank(24), 0);
+    assert_eq!(it.len(), 16);
+    assert_eq!(it.threshold(), 24);
+    assert_eq!(it.rank(40), 16);
+    assert_eq!(it.see_all([41, 41, 40, 42]), 2);
+    assert_eq!(it.iter().take(3).collect::<Vec<_>>(), [42, 41, 40]);
+}
+
+#[test]
+fn would_admit() {
+    let mut it = TopFewDistinct::<u32, 2>::new(5);
+    it.see_all([20, 30]);
+    assert!(!it.would_admit(30));
+    assert!(it.would_admit(31));
+    assert!(it.would_admit(21));
+    assert!(!it.would_admit(20));
+}
+
+#[test]
+fn next_admission_value() {
+    let mut it = TopFewDistinct::<u32, 4>::new(3);
+    assert_eq!(it.next_admission_value(), Some(4));
+    it.see_all([10, 11, 12, 14]);
+    // 11 and 12 are already retained, so they would be rejected.
+    assert_eq!(it.next_admission_value(), Some(13));
+    assert!(it.would_admit(13));
+    it.see(13);
+    assert_eq!(it.next_admission_value(), Some(15));
+    let mut it = TopFewDistinct::<u32, 2>::new(0);
+    it.see_all([u32::MAX, u32::MAX - 1]);
+    assert_eq!(it.next_admission_value(), None);
+}
+
+#[test]
+fn min_order() {
+    let mut it = TopFewDistinct::<u32, 4, Min>::new(100);
+    it.see_all([7, 7, 3, 50, 3, 1, 7]);
+    assert_eq!(it.check_invariants(), Ok(()));
+    assert_eq!(it.iter().collect::<Vec<_>>(), [1, 3, 7, 50]);
+    assert_eq!(it.rank(3), 0);
+    assert_eq!(it.rank(2), 3);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [1, 2, 3, 7]);
+}
+
+proptest! {
+    #[test]
+    fn proptest_distinct(
+        a in prop::collection::vec(0..64u32, 0..100),
+        b in prop::collection::vec(0..64u32, 0..100),
+    ) {
+        let mut x = TopFewDistinct::<u32>::new(3);
+        x.see_all(a.iter().copied());
+        let mut set: Vec<u32> = a.iter().copied().filter(|&v| v > 3).collect();
+        set.sort_unstable_by(|p, q| q.cmp(p));
+        set.dedup();
+        set.truncate(16);
+        prop_assert_eq!(x.iter().collect::<Vec<_>>(), set);
+
+        // Merging gives the same values as seeing all of them.
+        let mut y = TopFewDistinct::<u32>::new(0);
+        y.see_all(b.iter().copied());
+        let mut merged = x;
+        merged.merge(y.as_top_few());
+        prop_assert_eq!(merged.check_invariants(), Ok(()));
+        let mut z = TopFewDistinct::<u32>::new(3);
+        z.see_all(a.iter().chain(&b).copied());
+        prop_assert!(merged.iter().eq(z.iter()));
+    }
+}
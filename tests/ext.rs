@@ -0,0 +1,18 @@
+use top_few::{Top8, Top16, TopFewExt};
+
+#[test]
+fn inspect_top16_passes_everything_through() {
+    let data: Vec<u32> = (0..200).map(|i| i * 13 % 97).collect();
+    let mut top = Top16::new(0);
+    let passed: Vec<u32> = data.iter().copied().inspect_top16(&mut top).collect();
+    assert_eq!(passed, data);
+    assert_eq!(top, Top16::from(&data[..]));
+}
+
+#[test]
+fn inspect_top16_sees_only_what_is_consumed() {
+    let mut top = Top8::new(0);
+    let first: Vec<u32> = (1..100).inspect_top16(&mut top).take(3).collect();
+    assert_eq!(first, [1, 2, 3]);
+    assert!(top.iter().eq([3, 2, 1]));
+}
@@ -0,0 +1,59 @@
+use top_few::TopFewF32;
+
+// Returns the bits of the values, so that we can compare NaNs and signed zeros.
+fn bits(values: impl Iterator<Item = f32>) -> Vec<u32> {
+    values.map(f32::to_bits).collect()
+}
+
+#[test]
+fn ordinary_values() {
+    let mut it = TopFewF32::new(0.0);
+    for i in 1..20 {
+        it.see(i as f32 / 4.0);
+        it.see(-(i as f32));
+    }
+    let elements: Vec<f32> = it.iter().collect();
+    let expected: Vec<f32> = (4..20).rev().map(|i| i as f32 / 4.0).collect();
+    assert_eq!(elements, expected);
+    assert_eq!(it.max(), Some(4.75));
+    assert_eq!(it.cutoff(), 0.0);
+}
+
+#[test]
+fn special_values() {
+    let mut it = TopFewF32::new(f32::NEG_INFINITY);
+    for v in [
+        1.0,
+        f32::NAN,
+        -f32::NAN,
+        0.0,
+        -0.0,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        -1.0,
+    ] {
+        it.see(v);
+    }
+    // The negative NaN and the cutoff are not retained.
+    let expected = [f32::NAN, f32::INFINITY, 1.0, 0.0, -0.0, -1.0];
+    assert_eq!(bits(it.iter()), bits(expected.into_iter()));
+    assert_eq!(bits(it.iter().rev()), bits(expected.into_iter().rev()));
+
+    // -0.0 is below +0.0.
+    let mut it = TopFewF32::new(-0.0);
+    it.see(-0.0);
+    it.see(0.0);
+    assert_eq!(bits(it.iter()), bits([0.0].into_iter()));
+    it.set_cutoff(0.0);
+    assert_eq!(it.iter().len(), 0);
+}
+
+#[test]
+fn ranks_follow_total_cmp() {
+    let mut it = TopFewF32::new(f32::MIN);
+    assert_eq!(it.rank(1.0), 16);
+    assert_eq!(it.rank(f32::NAN), 16);
+    assert_eq!(it.rank(f32::INFINITY), 15);
+    assert_eq!(it.rank(-5.0), 13);
+    assert_eq!(it.rank(f32::MIN), 0);
+}
@@ -0,0 +1,31 @@
+use top_few::TopFewIndexed;
+
+#[test]
+fn positions_follow_values() {
+    let mut it = TopFewIndexed::new(0);
+    let data: Vec<u32> = (0..100).map(|i| i * 37 % 101).collect();
+    for &v in &data {
+        it.see(v);
+    }
+    assert_eq!(it.seen(), 100);
+    let mut expected: Vec<(u32, u64)> = data.iter().zip(0..).map(|(&v, i)| (v, i)).collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    expected.truncate(16);
+    assert_eq!(it.iter_with_positions().collect::<Vec<_>>(), expected);
+    assert!(it.iter().eq(expected.iter().map(|&(v, _)| v)));
+}
+
+#[test]
+fn ties_keep_the_oldest_position() {
+    let mut it = TopFewIndexed::new(0);
+    it.see(0); // position 0, not admitted
+    for _ in 0..20 {
+        it.see(5);
+    }
+    // All equal, so the first 16 are the ones kept, reported oldest first.
+    let positions: Vec<u64> = it.iter_with_positions().map(|(_, p)| p).collect();
+    assert_eq!(positions, (1..=16).collect::<Vec<_>>());
+    it.see(6);
+    assert_eq!(it.iter_with_positions().next(), Some((6, 21)));
+    assert_eq!(it.iter_with_positions().last(), Some((5, 15)));
+}
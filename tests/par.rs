@@ -0,0 +1,21 @@
+#![cfg(feature = "rayon")]
+
+use proptest::prelude::*;
+use top_few::Top16;
+use top_few::par::par_top16;
+
+proptest! {
+    #[test]
+    fn par_matches_sequential(data in prop::collection::vec(any::<u32>(), 0..20000), cutoff in any::<u32>()) {
+        let mut seq = Top16::new(cutoff);
+        seq.see_all(data.iter().copied());
+        prop_assert_eq!(par_top16(&data, cutoff), seq);
+    }
+}
+
+#[test]
+fn duplicates() {
+    let data: Vec<u32> = (0..100_000).map(|i| i % 10).collect();
+    let top = par_top16(&data, 0);
+    assert_eq!(top.to_sorted_vec(), [9; 16]);
+}
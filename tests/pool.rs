@@ -0,0 +1,51 @@
+#![cfg(feature = "alloc")]
+
+use top_few::{Top16, TopFewPool};
+
+#[test]
+fn acquired_tops_start_out_new() {
+    let mut pool = TopFewPool::with_capacity(2);
+    let (a, top) = pool.acquire(0);
+    top.see_all([5, 7]);
+    let (b, top) = pool.acquire(10);
+    top.see_all([5, 17]);
+    assert_ne!(a, b);
+    assert_eq!(pool.in_use(), 2);
+    assert_eq!(pool.get(a).to_sorted_vec(), [7, 5]);
+    assert_eq!(pool.get(b).to_sorted_vec(), [17]);
+
+    // A released one is reused, with the new cutoff and nothing left over.
+    pool.release(a);
+    assert_eq!(pool.in_use(), 1);
+    let (c, top) = pool.acquire(6);
+    assert_eq!(c, a);
+    assert_eq!(*top, Top16::new(6));
+    pool.get_mut(c).see(9);
+    assert_eq!(pool.get(c).to_sorted_vec(), [9]);
+}
+
+#[test]
+#[should_panic]
+fn releasing_an_unknown_index_panics() {
+    TopFewPool::new().release(0);
+}
+
+#[test]
+#[should_panic(expected = "released twice")]
+fn releasing_twice_panics() {
+    let mut pool = TopFewPool::new();
+    let (ix, _) = pool.acquire(0);
+    pool.release(ix);
+    pool.release(ix);
+}
+
+#[test]
+fn released_and_reacquired() {
+    let mut pool = TopFewPool::new();
+    let (ix, _) = pool.acquire(0);
+    pool.release(ix);
+    assert_eq!(pool.acquire(0).0, ix);
+    // It is in use again, so it can be released again.
+    pool.release(ix);
+    assert_eq!(pool.in_use(), 0);
+}
@@ -0,0 +1,17 @@
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use top_few::Top16;
+use top_few::proptest::any_top16;
+
+proptest! {
+    #[test]
+    fn generated_instances_are_valid(top in any_top16()) {
+        prop_assert_eq!(top.check_invariants(), Ok(()));
+        let values = top.to_sorted_vec();
+        prop_assert!(values.len() <= 16);
+        prop_assert!(values.iter().all(|&v| v > top.cutoff()));
+        prop_assert!(values.windows(2).all(|w| w[0] >= w[1]));
+        prop_assert_eq!(Top16::from_sorted_desc(top.cutoff(), &values), top);
+    }
+}
@@ -0,0 +1,84 @@
+use proptest::prelude::*;
+use top_few::{Top1, Top8, Top16, TopK};
+
+#[test]
+fn max() {
+    let mut it = Top1::new(3);
+    assert_eq!(it.max(), None);
+    it.see(2);
+    it.see(3);
+    assert_eq!(it.max(), None);
+    it.see(5);
+    it.see(4);
+    assert_eq!(it.max(), Some(5));
+    assert_eq!(it.threshold(), 5);
+}
+
+#[test]
+fn set_cutoff() {
+    let mut it = Top1::new(3);
+    it.set_cutoff(1);
+    assert_eq!(it.threshold(), 1);
+    it.see(2);
+    assert_eq!(it.max(), Some(2));
+    it.set_cutoff(0);
+    assert_eq!(it.max(), Some(2));
+    it.set_cutoff(2);
+    assert_eq!(it.max(), None);
+    assert_eq!(it.cutoff(), 2);
+    it.see(7);
+    it.set_cutoff(6);
+    assert_eq!(it.max(), Some(7));
+}
+
+// Generic code that works with any of them.
+fn max_of<K: TopK<Value = u32>>(mut top: K, values: &[u32]) -> Option<u32> {
+    for &v in values {
+        top.see(v);
+    }
+    top.max()
+}
+
+#[test]
+fn top_k() {
+    let values = [4, 9, 2, 7];
+    assert_eq!(max_of(Top1::new(0), &values), Some(9));
+    assert_eq!(max_of(Top8::new(0), &values), Some(9));
+    assert_eq!(max_of(Top16::new(0), &values), Some(9));
+    assert_eq!(max_of(Top1::new(9), &values), None);
+}
+
+proptest! {
+    #[test]
+    fn same_as_top16(values: Vec<u32>, cutoff: u32, new_cutoff: u32) {
+        let mut a = Top1::new(cutoff);
+        let mut b = Top16::new(cutoff);
+        for &v in &values {
+            a.see(v);
+            b.see(v);
+        }
+        prop_assert_eq!(a.max(), b.max());
+        TopK::set_cutoff(&mut a, new_cutoff);
+        TopK::set_cutoff(&mut b, new_cutoff);
+        prop_assert_eq!(a.max(), b.max());
+        prop_assert_eq!(a.threshold(), a.max().unwrap_or(new_cutoff));
+    }
+}
+
+// Generic code that looks at all of the retained values.
+fn describe<K: TopK<Value = u32>>(top: &K) -> (usize, Vec<u32>) {
+    (top.capacity(), top.iter().collect())
+}
+
+#[test]
+fn top_k_iter() {
+    let mut one = Top1::new(0);
+    assert_eq!(describe(&one), (1, vec![]));
+    let mut eight = Top8::new(0);
+    for v in 1..=10 {
+        one.see(v);
+        eight.see(v);
+    }
+    assert_eq!(describe(&one), (1, vec![10]));
+    assert_eq!(describe(&eight), (8, (3..=10).rev().collect()));
+}
@@ -1,6 +1,9 @@
+#![cfg(feature = "alloc")]
+
 use proptest::prelude::*;
-use std::collections::BinaryHeap;
-use top_few::Top16;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use top_few::{Max, Min, NewestWins, Order, ParseError, SeeOutcome, Top8, Top16, TopFew, TopN};
 
 #[test]
 fn ascending() {
@@ -92,6 +95,36 @@ fn higher_cutoff() {
     assert_eq!(elements, expected);
 }
 
+#[test]
+fn cutoff_extremes() {
+    let mut it = Top16::new(5);
+    for i in 1..10 {
+        it.see(i);
+    }
+
+    // Lowering to 0 keeps the values, and lets smaller ones in.
+    it.set_cutoff(0);
+    assert_eq!(it.cutoff(), 0);
+    it.see(1);
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [9, 8, 7, 6, 1]);
+
+    // Raising to near the top of the range.
+    for v in [u32::MAX, u32::MAX - 1, u32::MAX - 2] {
+        it.see(v);
+    }
+    it.set_cutoff(u32::MAX - 2);
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [u32::MAX, u32::MAX - 1]);
+    it.set_cutoff(u32::MAX - 1);
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [u32::MAX]);
+    it.set_cutoff(u32::MAX);
+    assert_eq!(it.iter().next(), None);
+    it.see(u32::MAX);
+    assert_eq!(it.max(), None);
+}
+
 #[test]
 fn peak() {
     let mut it = Top16::new(0);
@@ -176,3 +209,1533 @@ proptest! {
         assert_eq!(top16_values, heap_values);
     }
 }
+
+#[test]
+fn eq_ignores_insertion_order() {
+    let mut a = Top16::new(0);
+    let mut b = Top16::new(0);
+    for i in 1..40 {
+        a.see(i);
+        b.see(40 - i);
+    }
+    assert_eq!(a, b);
+
+    // Same values, different cutoffs.
+    let mut c = Top16::new(1);
+    for i in 1..40 {
+        c.see(i);
+    }
+    assert_ne!(a, c);
+
+    // Different values.
+    b.see(100);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn hash_matches_eq() {
+    let mut a = Top16::new(0);
+    let mut b = Top16::new(0);
+    for i in 1..40 {
+        a.see(i);
+        b.see(40 - i);
+    }
+    let mut set = HashSet::new();
+    set.insert(a);
+    set.insert(b);
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn display() {
+    let mut it = Top16::new(10);
+    assert_eq!(it.to_string(), "[]");
+    for i in [12, 5, 20, 15] {
+        it.see(i);
+    }
+    assert_eq!(it.to_string(), "[20, 15, 12]");
+}
+
+#[test]
+fn retain() {
+    let mut it = Top16::new(0);
+    for i in 1..20 {
+        it.see(i);
+    }
+    it.retain(|v| v % 2 == 0);
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [18, 16, 14, 12, 10, 8, 6, 4]);
+
+    // The freed slots can be filled again, and the order is maintained.
+    for i in [7, 21, 1] {
+        it.see(i);
+    }
+    let elements: Vec<u32> = it.iter().rev().collect();
+    assert_eq!(elements, [1, 4, 6, 7, 8, 10, 12, 14, 16, 18, 21]);
+}
+
+#[test]
+fn exact_size() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.iter().len(), 0);
+    for i in 1..10 {
+        it.see(i);
+    }
+    let mut iter = it.iter();
+    assert_eq!(iter.len(), 9);
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.size_hint(), (7, Some(7)));
+    for i in 10..30 {
+        it.see(i);
+    }
+    assert_eq!(it.iter().len(), 16);
+    assert_eq!(it.take(5).len(), 5);
+}
+
+fn top_of(values: impl IntoIterator<Item = u32>) -> top_few::IntoIter {
+    let mut it = Top16::new(0);
+    for v in values {
+        it.see(v);
+    }
+    it.into_iter()
+}
+
+#[test]
+fn into_iter() {
+    let elements: Vec<u32> = top_of(1..20).collect();
+    let expected: Vec<u32> = (4..20).rev().collect();
+    assert_eq!(elements, expected);
+
+    let elements: Vec<u32> = top_of(1..20).rev().collect();
+    let expected: Vec<u32> = (4..20).collect();
+    assert_eq!(elements, expected);
+
+    assert_eq!(top_of(1..5).len(), 4);
+    assert_eq!(top_of(0..1).next(), None);
+
+    // By reference, too.
+    let mut it = Top16::new(0);
+    it.see(3);
+    let mut total = 0;
+    for v in &it {
+        total += v;
+    }
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn contains() {
+    let mut it = Top16::new(10);
+    assert!(!it.contains(10));
+    for i in [12, 5, 20, 15, 10] {
+        it.see(i);
+    }
+    assert!(it.contains(12));
+    assert!(it.contains(20));
+    assert!(!it.contains(5));
+    assert!(!it.contains(10)); // The cutoff, which fills the unused slots.
+    assert!(!it.contains(13));
+}
+
+#[test]
+fn count_equal() {
+    let mut it = Top16::new(10);
+    for i in [12, 5, 20, 12, 15, 12, 20] {
+        it.see(i);
+    }
+    assert_eq!(it.count_equal(12), 3);
+    assert_eq!(it.count_equal(20), 2);
+    assert_eq!(it.count_equal(15), 1);
+    assert_eq!(it.count_equal(5), 0);
+    assert_eq!(it.count_equal(10), 0); // 9 slots hold the cutoff.
+}
+
+#[test]
+fn to_sorted_vec() {
+    let mut it = Top16::new(10);
+    assert_eq!(it.to_sorted_vec(), [0u32; 0]);
+    for i in [12, 5, 20, 15] {
+        it.see(i);
+    }
+    let v = it.to_sorted_vec();
+    assert_eq!(v, [20, 15, 12]);
+    assert_eq!(v.capacity(), 3);
+    assert_eq!(it.to_sorted_vec_asc(), [12, 15, 20]);
+}
+
+#[test]
+fn max_unchecked() {
+    let mut it = Top16::new(10);
+    assert_eq!(it.max(), None);
+    assert_eq!(it.max_unchecked(), 10);
+    it.see(12);
+    it.see(11);
+    assert_eq!(it.max(), Some(12));
+    assert_eq!(it.max_unchecked(), 12);
+}
+
+#[test]
+fn threshold() {
+    let mut it = Top16::new(10);
+    assert_eq!(it.threshold(), 10);
+    for i in 11..=26 {
+        it.see(i);
+        assert_eq!(it.threshold(), if i < 26 { 10 } else { 11 });
+    }
+    it.see(30);
+    assert_eq!(it.threshold(), 12);
+}
+
+#[test]
+fn from_sorted_desc() {
+    let values: Vec<u32> = (0..40).rev().collect();
+    let it = Top16::from_sorted_desc(0, &values);
+    let expected: Vec<u32> = (24..40).rev().collect();
+    assert_eq!(it.iter().collect::<Vec<u32>>(), expected);
+    assert_eq!(it.threshold(), 24);
+
+    // Fewer values than slots, some of them not above the cutoff.
+    let mut it = Top16::from_sorted_desc(5, &[9, 7, 7, 5, 2]);
+    assert_eq!(it.iter().collect::<Vec<u32>>(), [9, 7, 7]);
+    assert_eq!(it.threshold(), 5);
+
+    // Afterwards it behaves just like one built by seeing the values.
+    let mut seen = Top16::new(5);
+    for v in [9, 7, 7, 5, 2] {
+        seen.see(v);
+    }
+    for v in [8, 6, 20] {
+        assert_eq!(it.rank(v), seen.rank(v));
+    }
+    assert_eq!(it, seen);
+}
+
+#[test]
+fn see_all() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.see_all(1..20), 19);
+    assert_eq!(it.see_all([0, 3, 4, 5, 30]), 2); // 0 is the cutoff, 4 the threshold.
+    let expected: Vec<u32> = [30].into_iter().chain((5..20).rev()).collect();
+    assert_eq!(it.iter().collect::<Vec<u32>>(), expected);
+}
+
+#[test]
+fn inclusive_cutoff() {
+    let mut it = Top16::new_inclusive(10);
+    for i in [12, 9, 10, 15, 10] {
+        it.see(i);
+    }
+    assert_eq!(it.iter().collect::<Vec<u32>>(), [15, 12, 10, 10]);
+    assert_eq!(it.cutoff(), 9);
+    assert!(it.contains(10));
+}
+
+#[test]
+#[should_panic]
+fn inclusive_cutoff_of_zero() {
+    let _ = Top16::new_inclusive(0);
+}
+
+#[test]
+fn would_rank() {
+    let mut it = Top16::new(0);
+    for i in 1..20 {
+        it.see(i * 2);
+    }
+    // 8 10 12 ... 38
+    let before = it;
+    assert_eq!(
+        it.ranks(&[0, 8, 9, 10, 11, 38, 39]),
+        [0, 0, 1, 1, 2, 15, 16]
+    );
+    assert_eq!(it.would_rank(11), 2);
+    assert_eq!(it, before);
+    assert_eq!(it.rank(11), 2);
+}
+
+#[test]
+fn default() {
+    let it = Top16::default();
+    assert_eq!(it.cutoff(), 0);
+    assert_eq!(it, Top16::new(0));
+}
+
+#[test]
+fn signed() {
+    let mut it = TopFew::<i32>::new(-10);
+    for v in [3, -5, -10, -20, 0, 7, -1, i32::MIN, i32::MAX, -9] {
+        it.see(v);
+    }
+    let elements: Vec<i32> = it.iter().collect();
+    assert_eq!(elements, [i32::MAX, 7, 3, 0, -1, -5, -9]);
+    assert_eq!(it.max(), Some(i32::MAX));
+    assert_eq!(it.would_rank(-6), 10); // above the nine unused slots
+
+    let mut it = TopFew::<i32>::new(-100);
+    for v in -99..0 {
+        it.see(v);
+    }
+    assert!(it.iter().eq((-16..0).rev()));
+    assert_eq!(it.threshold(), -16);
+    assert_eq!(it.rank(-3), 13); // below the existing -3
+    it.set_cutoff(-8);
+    assert_eq!(it.to_sorted_vec(), [-1, -2, -3, -3, -4, -5, -6, -7]);
+}
+
+#[test]
+fn merge() {
+    let mut a = Top16::new(5);
+    let mut b = Top16::new(0);
+    a.see_all((10..30).step_by(2));
+    b.see_all((1..20).step_by(3));
+    let mut all = Top16::new(5);
+    all.see_all((10..30).step_by(2).chain((1..20).step_by(3)));
+    assert_eq!(a.merged(&b), all);
+    // The receiver's cutoff applies.
+    b.merge(&a);
+    assert_eq!(
+        b.to_sorted_vec(),
+        [28, 26, 24, 22, 20, 19, 18, 16, 16, 14, 13, 12, 10, 10, 7, 4]
+    );
+}
+
+#[test]
+fn get() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.get(0), None);
+    it.see_all([5, 9, 3]);
+    assert_eq!(it.get(0), Some(9));
+    assert_eq!(it.get(1), Some(5));
+    assert_eq!(it.get(2), Some(3));
+    assert_eq!(it.get(3), None);
+    it.see_all(10..40);
+    for i in 0..16 {
+        assert_eq!(it.get(i), Some(39 - i as u32));
+    }
+    assert_eq!(it.get(16), None);
+    assert_eq!(it.get(usize::MAX), None);
+}
+
+#[test]
+fn clone_iter() {
+    let mut it = Top16::new(0);
+    it.see_all(1..10);
+    let mut iter = it.iter();
+    iter.next();
+    let snapshot = iter.clone();
+    assert_eq!(iter.max(), Some(8));
+    assert!(snapshot.eq((1..9).rev()));
+}
+
+#[test]
+fn nth_largest() {
+    let mut it = Top16::new(0);
+    it.see_all(1..=10);
+    for k in 0..12 {
+        assert_eq!(it.nth_largest(k), it.iter().nth(k));
+        assert_eq!(it.nth_largest(k), (1..=10).rev().nth(k));
+    }
+    let mut iter = it.iter();
+    assert_eq!(iter.nth(2), Some(8));
+    assert_eq!(iter.next_back(), Some(1));
+    assert_eq!(iter.nth(5), Some(2));
+    assert_eq!(iter.nth(1), None);
+    let mut iter = it.iter();
+    assert_eq!(iter.nth(10), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn cutoff_of_max_stays_empty() {
+    let mut it = Top16::new(u32::MAX);
+    it.see(u32::MAX);
+    assert_eq!(it.rank(u32::MAX), 0);
+    assert_eq!(it.see_all([0, 1, u32::MAX]), 0);
+    assert_eq!(it.would_rank(u32::MAX), 0);
+    assert_eq!(it.max(), None);
+    assert_eq!(it.get(0), None);
+    assert!(!it.contains(u32::MAX));
+    assert_eq!(it.count_equal(u32::MAX), 0);
+    assert_eq!(it.iter().len(), 0);
+    assert_eq!(it.take(100).next(), None);
+    assert_eq!(it.threshold(), u32::MAX);
+
+    let mut full = Top16::new(0);
+    full.see_all([u32::MAX; 20]);
+    it.merge(&full);
+    assert_eq!(it.iter().next(), None);
+    assert_eq!(Top16::from_sorted_desc(u32::MAX, &[u32::MAX; 3]), it);
+
+    // Setting it to u32::MAX again, or lowering it, works as usual.
+    it.set_cutoff(u32::MAX);
+    assert_eq!(it.iter().next(), None);
+    it.set_cutoff(0);
+    it.see(u32::MAX);
+    assert_eq!(it.to_sorted_vec(), [u32::MAX]);
+}
+
+#[test]
+fn undo() {
+    let mut it = Top16::new(0);
+    it.see_all([5, 9, 3]);
+    let before = it.snapshot();
+    let token = it.see_undoable(7);
+    assert_eq!(it.to_sorted_vec(), [9, 7, 5, 3]);
+    it.undo(token);
+    assert_eq!(it, before);
+
+    it.see_all(10..30);
+    let before = it.snapshot();
+    for v in [0, 14, 20, 100] {
+        let token = it.see_undoable(v);
+        it.undo(token);
+        assert_eq!(it.to_sorted_vec(), before.to_sorted_vec());
+        assert_eq!(it.threshold(), before.threshold());
+    }
+    it.see(50);
+    it.restore(&before);
+    assert_eq!(it, before);
+}
+
+#[test]
+fn truncate_to() {
+    let mut it = Top16::new(0);
+    it.see_all(1..30);
+    it.truncate_to(20);
+    assert_eq!(it.iter().len(), 16);
+    it.truncate_to(5);
+    assert_eq!(it.to_sorted_vec(), [29, 28, 27, 26, 25]);
+    assert_eq!(it.threshold(), 0);
+    it.see(1);
+    assert_eq!(it.to_sorted_vec(), [29, 28, 27, 26, 25, 1]);
+    it.truncate_to(0);
+    assert_eq!(it, Top16::new(0));
+}
+
+#[test]
+fn check_invariants() {
+    let mut it = Top16::new(3);
+    assert_eq!(it.check_invariants(), Ok(()));
+    for i in 0..50 {
+        it.see(i * 7 % 23);
+        assert_eq!(it.check_invariants(), Ok(()));
+    }
+    it.retain(|v| v % 2 == 0);
+    assert_eq!(it.check_invariants(), Ok(()));
+    it.set_cutoff(10);
+    assert_eq!(it.check_invariants(), Ok(()));
+    it.merge(&Top16::from_sorted_desc(0, &[30, 20, 10, 5]));
+    assert_eq!(it.check_invariants(), Ok(()));
+    let token = it.see_undoable(25);
+    it.undo(token);
+    assert_eq!(it.check_invariants(), Ok(()));
+    it.truncate_to(2);
+    assert_eq!(it.check_invariants(), Ok(()));
+}
+
+#[test]
+fn insert() {
+    use top_few::InsertResult;
+    let mut it = Top16::new(0);
+    let result = |rank, evicted| InsertResult { rank, evicted };
+    assert_eq!(it.insert(0), result(0, None));
+    assert_eq!(it.insert(5), result(16, None));
+    it.see_all(10..25);
+    assert_eq!(it.insert(3), result(0, None));
+    assert_eq!(it.insert(12), result(3, Some(5)));
+    assert_eq!(it.insert(30), result(16, Some(10)));
+    assert_eq!(it.threshold(), 11);
+}
+
+#[test]
+fn newest_wins() {
+    // A new equal value goes above the existing one rather than below it.
+    let mut oldest = Top16::new(0);
+    let mut newest = TopFew::<u32, 16, NewestWins>::new(0);
+    oldest.see_all(1..=16);
+    newest.see_all(1..=16);
+    assert_eq!(oldest.rank(8), 7);
+    assert_eq!(newest.rank(8), 8);
+    assert_eq!(oldest.to_sorted_vec(), newest.to_sorted_vec());
+
+    let mut oldest = Top16::new(0);
+    let mut newest = TopFew::<u32, 16, NewestWins>::new(0);
+    assert_eq!(oldest.see_all([7; 20]), 16);
+    assert_eq!(newest.see_all([7; 20]), 20);
+    assert_eq!(oldest.rank(7), 0);
+    assert_eq!(newest.rank(7), 16);
+    assert_eq!(newest.rank(0), 0);
+    assert_eq!(newest.to_sorted_vec(), [7; 16]);
+
+    // Not full yet, so the cutoff is the threshold, and it is still excluded.
+    let mut newest = TopFew::<u32, 16, NewestWins>::new(3);
+    assert_eq!(newest.see_all([3, 3, 4, 4, 5]), 3);
+    assert_eq!(newest.rank(4), 15);
+    assert_eq!(newest.to_sorted_vec(), [5, 4, 4, 4]);
+}
+
+#[test]
+fn elements_unordered() {
+    let mut it = Top16::new(2);
+    it.see_all([9, 1, 5, 7]);
+    let mut elements = *it.elements_unordered();
+    elements.sort();
+    assert_eq!(
+        elements,
+        [[2; 13].as_slice(), &[5, 7, 9]].concat().as_slice()
+    );
+    it.see_all(10..30);
+    let sum: u32 = it.elements_unordered().iter().sum();
+    assert_eq!(sum, it.iter().sum());
+}
+
+// Checks a TopN against sorting, for values with plenty of duplicates.
+fn check_top_n<const N: usize, O: Order<Direction = Max>>(data: &[u32])
+where
+    top_few::Cap<N>: top_few::Capacity,
+{
+    let mut it = TopFew::<u32, N, O>::new(3);
+    let mut ranks = Vec::new();
+    for &v in data {
+        ranks.push(it.rank(v));
+        assert_eq!(it.check_invariants(), Ok(()));
+    }
+    let mut expected: Vec<u32> = data.iter().copied().filter(|&v| v > 3).collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    expected.truncate(N);
+    assert_eq!(it.to_sorted_vec(), expected);
+    assert!(ranks.iter().all(|&r| r <= N));
+    it.retain(|v| v % 2 == 0);
+    expected.retain(|v| v % 2 == 0);
+    assert_eq!(it.to_sorted_vec(), expected);
+    assert_eq!(it.check_invariants(), Ok(()));
+}
+
+proptest! {
+    #[test]
+    fn proptest_top_n(data in prop::collection::vec(0..64u32, 0..200)) {
+        check_top_n::<1, Max>(&data);
+        check_top_n::<3, Max>(&data);
+        check_top_n::<8, Max>(&data);
+        check_top_n::<9, Max>(&data);
+        check_top_n::<12, Max>(&data);
+        check_top_n::<16, Max>(&data);
+        check_top_n::<17, Max>(&data);
+        check_top_n::<25, Max>(&data);
+        check_top_n::<1, NewestWins>(&data);
+        check_top_n::<3, NewestWins>(&data);
+        check_top_n::<8, NewestWins>(&data);
+        check_top_n::<9, NewestWins>(&data);
+        check_top_n::<12, NewestWins>(&data);
+        check_top_n::<16, NewestWins>(&data);
+        check_top_n::<17, NewestWins>(&data);
+        check_top_n::<25, NewestWins>(&data);
+    }
+}
+
+#[test]
+fn top8() {
+    let mut it = Top8::new(0);
+    assert_eq!(it.see_all(1..20), 19);
+    assert!(it.iter().eq((12..20).rev()));
+    assert_eq!(it.rank(15), 3);
+    assert_eq!(it.take(3).collect::<Vec<_>>(), [19, 18, 17]);
+    assert_eq!(it.get(7), Some(13));
+    assert_eq!(it.get(8), None);
+    assert!(std::mem::size_of::<Top8>() < std::mem::size_of::<Top16>());
+
+    let mut it = TopN::<25>::new(0);
+    it.see_all(1..=100);
+    assert!(it.iter().eq((76..=100).rev()));
+    assert_eq!(it.rank(101), 25);
+    assert_eq!(it.rank(100), 23);
+}
+
+#[test]
+fn raise_cutoff_to_current_min() {
+    let mut it = Top16::new(0);
+    it.see_all([4, 8, 8]);
+    it.raise_cutoff_to_current_min();
+    assert_eq!(it.cutoff(), 0);
+    assert_eq!(it.to_sorted_vec(), [8, 8, 4]);
+
+    it.see_all([5; 13]);
+    it.raise_cutoff_to_current_min();
+    assert_eq!(it.cutoff(), 4);
+    assert_eq!(it.to_sorted_vec(), [[8, 8].as_slice(), &[5; 13]].concat());
+    it.raise_cutoff_to_current_min();
+    assert_eq!(it.cutoff(), 4);
+    it.see_all([3, 4, 6]);
+    assert_eq!(
+        it.to_sorted_vec(),
+        [[8, 8, 6].as_slice(), &[5; 13]].concat()
+    );
+    it.raise_cutoff_to_current_min();
+    assert_eq!(it.cutoff(), 5);
+    assert_eq!(it.to_sorted_vec(), [8, 8, 6]);
+}
+
+#[test]
+fn see_top_of() {
+    let mut shard = Top16::new(0);
+    shard.see_all([50, 40, 30, 20, 10]);
+    let mut it = Top16::new(15);
+    it.see_all([35, 5]);
+    it.see_top_of(&shard, 3);
+    assert_eq!(it.to_sorted_vec(), [50, 40, 35, 30]);
+    it.see_top_of(&shard, 0);
+    assert_eq!(it.iter().len(), 4);
+    it.see_top_of(&shard, 100);
+    assert_eq!(it.to_sorted_vec(), [50, 50, 40, 40, 35, 30, 30, 20]);
+}
+
+#[test]
+fn sums() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.sum(), 0);
+    assert_eq!(it.checked_sum(), Some(0));
+    it.see_all(1..=10);
+    assert_eq!(it.sum(), 55);
+    assert_eq!(it.checked_sum(), Some(55));
+    it.see_all([u32::MAX / 16; 16]);
+    assert_eq!(it.checked_sum(), Some(u32::MAX / 16 * 16));
+    it.see(u32::MAX / 8);
+    assert_eq!(it.checked_sum(), None);
+    assert_eq!(
+        it.sum(),
+        (u32::MAX / 16) as u64 * 15 + (u32::MAX / 8) as u64
+    );
+}
+
+#[test]
+fn iter_ranked() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.iter_ranked().next(), None);
+    assert_eq!(it.rank(7), 16);
+    assert_eq!(it.rank(3), 15);
+    assert_eq!(it.rank(5), 15);
+    assert_eq!(
+        it.iter_ranked().collect::<Vec<_>>(),
+        [(16, 7), (15, 5), (14, 3)]
+    );
+    it.see_all(10..30);
+    let ranked: Vec<(usize, u32)> = it.iter_ranked().rev().collect();
+    assert_eq!(ranked, (1..=16).zip(14..30).collect::<Vec<_>>());
+    assert_eq!(it.iter_ranked().len(), 16);
+}
+
+#[test]
+fn reinit() {
+    let mut it = Top16::new(0);
+    it.see_all(1..30);
+    it.reinit(5);
+    assert_eq!(it, Top16::new(5));
+    assert_eq!(it.threshold(), 5);
+
+    let mut it = TopFew::<u32, 16, NewestWins>::new(0);
+    it.see_all(1..30);
+    it.reinit(50);
+    assert_eq!(it, TopFew::<u32, 16, NewestWins>::new(50));
+}
+
+#[test]
+fn gaps() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.gaps().next(), None);
+    it.see(10);
+    assert_eq!(it.gaps().next(), None);
+    it.see_all([12, 20, 20, 50]);
+    assert_eq!(it.gaps().collect::<Vec<_>>(), [2, 8, 0, 30]);
+    assert_eq!(it.gaps().max(), Some(30));
+}
+
+// A value that is compared by its key alone, so that equal values can be told apart.
+#[derive(Clone, Copy, Debug)]
+struct Tagged {
+    key: u32,
+    tag: u32,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+fn tags<O: Order>(it: &TopFew<Tagged, 16, O>) -> Vec<u32> {
+    it.iter().map(|t| t.tag).collect()
+}
+
+#[test]
+fn ties_keep_the_oldest() {
+    let cutoff = Tagged { key: 0, tag: 0 };
+
+    // All equal: the first 16 are kept, and reported in the order they were seen.
+    let mut it = TopFew::<Tagged>::new(cutoff);
+    for tag in 1..=40 {
+        it.see(Tagged { key: 5, tag });
+    }
+    assert_eq!(tags(&it), (1..=16).collect::<Vec<_>>());
+
+    // Equal values interleaved with others keep their relative order,
+    // and when one of them has to go, it is the newest one.
+    let mut it = TopFew::<Tagged>::new(cutoff);
+    for tag in 1..=30 {
+        let key = if tag % 2 == 0 { 5 } else { 1 };
+        it.see(Tagged { key, tag });
+    }
+    let fives = |it: &TopFew<Tagged>| -> Vec<u32> {
+        it.iter().filter(|t| t.key == 5).map(|t| t.tag).collect()
+    };
+    assert_eq!(fives(&it), (2..=30).step_by(2).collect::<Vec<_>>());
+    assert_eq!(it.iter().next_back().map(|t| t.tag), Some(1));
+    it.see(Tagged { key: 99, tag: 31 });
+    it.see(Tagged { key: 99, tag: 32 });
+    assert_eq!(fives(&it), (2..=28).step_by(2).collect::<Vec<_>>());
+    assert_eq!(tags(&it)[..2], [31, 32]);
+
+    // The oldest-first order survives removing other values.
+    it.retain(|t| t.key == 5 || t.tag == 32);
+    assert_eq!(tags(&it)[0], 32);
+    assert_eq!(fives(&it), (2..=28).step_by(2).collect::<Vec<_>>());
+
+    // With newer values winning ties, it is the other way around.
+    let mut it = TopFew::<Tagged, 16, NewestWins>::new(cutoff);
+    for tag in 1..=40 {
+        it.see(Tagged { key: 5, tag });
+    }
+    assert_eq!(tags(&it), (25..=40).rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn min_order_keeps_the_smallest() {
+    let mut it = TopFew::<u32, 4, Min>::new(100);
+    for v in [50, 150, 7, 100, 30, 99, 8, 1] {
+        it.see(v);
+    }
+    assert_eq!(it.to_sorted_vec(), [1, 7, 8, 30]);
+    assert_eq!(it.max(), Some(1));
+    assert_eq!(it.threshold(), 30);
+    assert_eq!(it.rank(0), 4);
+    assert_eq!(it.rank(30), 0);
+    it.set_cutoff(5);
+    assert_eq!(it.to_sorted_vec(), [0, 1]);
+    assert_eq!(it.check_invariants(), Ok(()));
+}
+
+// A Min TopFew should behave exactly like a Max one over the reversed values.
+fn check_min_order<O: Order<Direction = Min>, P: Order<Direction = Max>>(data: &[u32]) {
+    let mut min = TopFew::<u32, 16, O>::new(60);
+    let mut max = TopFew::<Reverse<u32>, 16, P>::new(Reverse(60));
+    for &v in data {
+        assert_eq!(min.rank(v), max.rank(Reverse(v)));
+    }
+    assert_eq!(min.check_invariants(), Ok(()));
+    assert!(min.iter().eq(max.iter().map(|Reverse(v)| v)));
+}
+
+proptest! {
+    #[test]
+    fn proptest_min_order(data in prop::collection::vec(0..64u32, 0..200)) {
+        check_min_order::<Min, Max>(&data);
+        check_min_order::<NewestWins<Min>, NewestWins>(&data);
+    }
+}
+
+#[test]
+fn lowering_the_cutoff_recovers_nothing() {
+    let mut it = Top8::new(10);
+    for v in [5, 15, 8, 20] {
+        it.see(v);
+    }
+    // Only two values made it in, so 5 and 8 are lost for good.
+    assert!(!it.can_lower_cutoff());
+    it.set_cutoff(0);
+    assert_eq!(it.to_sorted_vec(), [20, 15]);
+
+    // Once it is full, nothing it missed could have made the top 8.
+    for v in 11..20 {
+        it.see(v);
+    }
+    assert!(it.can_lower_cutoff());
+    let before = it.to_sorted_vec();
+    it.set_cutoff(0);
+    assert_eq!(it.to_sorted_vec(), before);
+}
+
+#[test]
+fn from_slice() {
+    let data: Vec<u32> = (0..50).map(|i| i * 7 % 31).collect();
+    let mut expected = Top16::new(0);
+    for &v in &data {
+        expected.see(v);
+    }
+    assert_eq!(Top16::from(&data[..]), expected);
+    assert_eq!(Top8::from(&[0, 2, 0][..]).to_sorted_vec(), [2]);
+}
+
+#[test]
+fn position_of() {
+    let mut it = Top8::new(0);
+    for v in [10, 30, 20, 20] {
+        it.see(v);
+    }
+    // Four empty slots at the bottom, then 10, the two 20s and 30.
+    assert_eq!(it.position_of(30), Some(8));
+    assert_eq!(it.position_of(20), Some(6));
+    assert_eq!(it.position_of(10), Some(5));
+    assert_eq!(it.position_of(15), None);
+    assert_eq!(it.position_of(0), None);
+    for v in [40, 50, 60, 70, 80] {
+        it.see(v);
+    }
+    assert_eq!(it.position_of(10), None);
+    assert_eq!(it.position_of(20), Some(1));
+    assert_eq!(it.position_of(80), Some(8));
+}
+
+#[test]
+fn approx_quantile() {
+    let mut it = Top16::new(0);
+    for v in 1..=80 {
+        it.see(v);
+    }
+    // 0.875 * 80 = 70, and the 70th smallest of 1..=80 is 70, which is retained.
+    assert_eq!(it.approx_quantile(0.875, 80), Some(70));
+    assert_eq!(it.approx_quantile(1.0, 80), Some(80));
+    // 0.8 * 80 = 64, which is below the retained values.
+    assert_eq!(it.approx_quantile(0.8, 80), None);
+    assert_eq!(it.approx_quantile(1.5, 80), None);
+    assert_eq!(it.approx_quantile(f64::NAN, 80), None);
+    assert_eq!(it.approx_quantile(0.9, 0), None);
+
+    let mut it = Top16::new(0);
+    for v in 1..=1_000_000 {
+        it.see(v);
+    }
+    assert_eq!(it.approx_quantile(0.99999, 1_000_000), Some(999_990));
+    assert_eq!(it.approx_quantile(0.999, 1_000_000), None);
+}
+
+#[test]
+fn iter_grouped() {
+    let mut it = Top8::new(0);
+    assert_eq!(it.iter_grouped().count(), 0);
+    for v in [3, 0, 5, 3, 9, 5, 3] {
+        it.see(v);
+    }
+    assert_eq!(
+        it.iter_grouped().collect::<Vec<_>>(),
+        [(9, 1), (5, 2), (3, 3)]
+    );
+}
+
+fn check_merge_sorted<const N: usize, O: Order>(a: &[u32], b: &[u32])
+where
+    top_few::Cap<N>: top_few::Capacity,
+{
+    let (mut x, mut y) = (TopFew::<u32, N, O>::new(10), TopFew::<u32, N, O>::new(5));
+    x.see_all(a.iter().copied());
+    y.see_all(b.iter().copied());
+    let mut sorted = x;
+    sorted.merge_sorted(&y);
+    assert_eq!(sorted.check_invariants(), Ok(()));
+    assert_eq!(sorted, x.merged(&y));
+}
+
+proptest! {
+    #[test]
+    fn proptest_merge_sorted(
+        a in prop::collection::vec(0..64u32, 0..40),
+        b in prop::collection::vec(0..64u32, 0..40),
+    ) {
+        check_merge_sorted::<16, Max>(&a, &b);
+        check_merge_sorted::<5, Max>(&a, &b);
+        check_merge_sorted::<25, Max>(&a, &b);
+        check_merge_sorted::<16, NewestWins>(&a, &b);
+        check_merge_sorted::<5, NewestWins>(&a, &b);
+        check_merge_sorted::<25, NewestWins>(&a, &b);
+    }
+}
+
+#[test]
+fn merge_sorted_ties() {
+    let mut a = TopFew::<Tagged, 2>::new(Tagged { key: 0, tag: 0 });
+    let mut b = a;
+    a.see(Tagged { key: 5, tag: 1 });
+    b.see(Tagged { key: 5, tag: 2 });
+    b.see(Tagged { key: 5, tag: 3 });
+    let mut expected = a;
+    expected.merge(&b);
+    a.merge_sorted(&b);
+    let tags = |t: &TopFew<Tagged, 2>| t.iter().map(|v| v.tag).collect::<Vec<_>>();
+    assert_eq!(tags(&a), [1, 2]);
+    assert_eq!(tags(&a), tags(&expected));
+}
+
+#[test]
+fn range() {
+    let mut it = Top8::new(0);
+    assert_eq!(it.range(), None);
+    it.see(5);
+    assert_eq!(it.range(), Some((5, 5)));
+    it.see_all([3, 9, 0]);
+    assert_eq!(it.range(), Some((3, 9)));
+    it.see_all(10..20);
+    assert_eq!(it.range(), Some((12, 19)));
+}
+
+#[test]
+fn histogram() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.histogram(3), [0, 0, 0]);
+    it.see_all([7, 7, 7]);
+    assert_eq!(it.histogram(3), [3, 0, 0]);
+    it.see_all([10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
+    // The range is 7 to 19, so the buckets are 7..11, 11..15 and 15..=19.
+    assert_eq!(it.histogram(3), [4, 4, 5]);
+    assert_eq!(it.histogram(1), [13]);
+    assert_eq!(it.histogram(0), []);
+}
+
+#[test]
+fn len_and_is_empty() {
+    let mut it = Top8::new(10);
+    assert!(it.is_empty());
+    assert_eq!(it.len(), 0);
+    it.see_all([1, 5, 10]);
+    assert!(it.is_empty());
+    it.see(11);
+    assert!(!it.is_empty());
+    assert_eq!(it.len(), 1);
+    it.see_all(20..40);
+    assert_eq!(it.len(), 8);
+    it.set_cutoff(35);
+    assert_eq!(it.len(), 4);
+}
+
+#[test]
+fn map_in_place() {
+    let mut it = Top8::new(10);
+    it.see_all([12, 30, 20, 11]);
+    it.map_monotonic(|v| v * 3);
+    assert_eq!(it.to_sorted_vec(), [90, 60, 36, 33]);
+    assert_eq!(it.check_invariants(), Ok(()));
+    // Values mapped to the cutoff or below are removed.
+    it.map_in_place(|v| v.saturating_sub(40));
+    assert_eq!(it.to_sorted_vec(), [50, 20]);
+    assert_eq!(it.check_invariants(), Ok(()));
+    it.see(15);
+    assert_eq!(it.to_sorted_vec(), [50, 20, 15]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "did not preserve the order")]
+fn map_monotonic_checks_the_order() {
+    let mut it = Top8::new(0);
+    it.see_all([1, 2, 3]);
+    it.map_monotonic(|v| 10 - v);
+}
+
+#[test]
+fn see_reporting_saturation() {
+    let mut it = Top8::new(0);
+    assert!(!it.see_reporting_saturation(5));
+    assert!(it.see_reporting_saturation(u32::MAX));
+    assert_eq!(it.max(), Some(u32::MAX));
+    // Once it is full of them, another one is not admitted.
+    for _ in 0..7 {
+        assert!(it.see_reporting_saturation(u32::MAX));
+    }
+    assert!(!it.see_reporting_saturation(u32::MAX));
+}
+
+#[test]
+fn next_admission_value() {
+    let mut it = Top8::new(3);
+    assert_eq!(it.next_admission_value(), 4);
+    it.see_all(10..18);
+    assert_eq!(it.next_admission_value(), 11);
+    assert!(!it.would_admit(10));
+    assert!(it.would_admit(11));
+    // Ties get in once a newest-wins TopFew is full, but not before.
+    let mut it = TopFew::<u32, 8, NewestWins>::new(3);
+    assert_eq!(it.next_admission_value(), 4);
+    it.see_all(10..18);
+    assert_eq!(it.next_admission_value(), 10);
+    assert!(it.would_admit(10));
+    // Nothing gets in, but it saturates.
+    let mut it = Top8::new(0);
+    it.see_all([u32::MAX; 8]);
+    assert_eq!(it.next_admission_value(), u32::MAX);
+}
+
+#[test]
+fn percentile() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.percentile(50.0), None);
+    it.see(7);
+    assert_eq!(it.percentile(0.0), Some(7));
+    assert_eq!(it.percentile(100.0), Some(7));
+    it.see_all([10, 20, 30, 40]);
+    assert_eq!(it.percentile(0.0), Some(7));
+    assert_eq!(it.percentile(25.0), Some(10));
+    assert_eq!(it.percentile(50.0), Some(20));
+    // Position 2.6 of 0 to 4, so six tenths of the way from 20 to 30.
+    assert_eq!(it.percentile(65.0), Some(26));
+    assert_eq!(it.percentile(100.0), Some(40));
+    assert_eq!(it.percentile(100.5), None);
+    assert_eq!(it.percentile(-1.0), None);
+}
+
+#[test]
+fn see_returning_threshold() {
+    let mut it = TopN::<3>::new(10);
+    assert_eq!(it.see_returning_threshold(20), 10);
+    assert_eq!(it.see_returning_threshold(5), 10);
+    assert_eq!(it.see_returning_threshold(30), 10);
+    assert_eq!(it.see_returning_threshold(25), 20);
+    assert_eq!(it.see_returning_threshold(40), 25);
+    assert_eq!(it.see_returning_threshold(21), 25);
+    assert_eq!(it.threshold(), 25);
+}
+
+#[test]
+fn merge_all() {
+    assert_eq!(Top16::merge_all([]), None);
+    let data: Vec<u32> = (0..500).map(|i| i * 37 % 499).collect();
+    let parts = data.chunks(7).map(|chunk| {
+        let mut top = Top16::new(3);
+        top.see_all(chunk.iter().copied());
+        top
+    });
+    let mut all = Top16::new(3);
+    all.see_all(data.iter().copied());
+    assert_eq!(Top16::merge_all(parts), Some(all));
+}
+
+#[test]
+fn would_admit() {
+    let mut it = TopN::<2>::new(10);
+    assert!(!it.would_admit(10));
+    assert!(it.would_admit(11));
+    it.see_all([20, 30]);
+    assert!(!it.would_admit(20));
+    assert!(it.would_admit(21));
+    let mut newest = TopFew::<u32, 2, NewestWins>::new(10);
+    newest.see_all([20, 30]);
+    assert!(newest.would_admit(20));
+}
+
+#[test]
+fn raw_parts_round_trip() {
+    let mut it = Top16::new(3);
+    it.see_all((0..40).map(|i| i * 17 % 41));
+    let (cutoff, threshold, sorted_ixs, elements) = it.raw_parts();
+    assert_eq!((cutoff, threshold), (3, it.threshold()));
+    assert_eq!(elements, *it.elements_unordered());
+    // SAFETY: the parts came from raw_parts() on a Top16.
+    let copy = unsafe { Top16::from_raw_parts(cutoff, threshold, sorted_ixs, elements) };
+    assert_eq!(copy, it);
+    assert_eq!(copy.check_invariants(), Ok(()));
+}
+
+#[test]
+fn copy_sorted_into() {
+    let mut it = Top8::new(0);
+    let mut out = [0; 8];
+    assert_eq!(it.copy_sorted_into(&mut out), 0);
+    it.see_all([4, 9, 2]);
+    assert_eq!(it.copy_sorted_into(&mut out), 3);
+    assert_eq!(out[..3], [9, 4, 2]);
+    assert_eq!(it.copy_sorted_into(&mut out[..3]), 3);
+}
+
+#[test]
+#[should_panic]
+fn copy_sorted_into_too_short() {
+    let it = Top8::from(&[4, 9, 2][..]);
+    it.copy_sorted_into(&mut [0; 2]);
+}
+
+#[test]
+fn lowering_the_cutoff_updates_the_unfilled_slots() {
+    let mut it = Top8::new(10);
+    it.see_all([15, 12, 30]);
+    it.set_cutoff(5);
+    assert_eq!(it.check_invariants(), Ok(()));
+    assert_eq!(it.threshold(), 5);
+    assert_eq!(
+        it.elements_unordered().iter().filter(|&&v| v == 5).count(),
+        5
+    );
+    assert_eq!(it.to_sorted_vec(), [30, 15, 12]);
+    // Values between the two cutoffs are admitted now.
+    it.see_all([6, 8, 10]);
+    assert_eq!(it.to_sorted_vec(), [30, 15, 12, 10, 8, 6]);
+    // Lowering it again when full changes nothing but the cutoff.
+    it.see_all([7, 9]);
+    let before = it.to_sorted_vec();
+    it.set_cutoff(0);
+    assert_eq!(it.check_invariants(), Ok(()));
+    assert_eq!((it.cutoff(), it.threshold()), (0, 6));
+    assert_eq!(it.to_sorted_vec(), before);
+}
+
+#[test]
+fn capacity() {
+    assert_eq!(Top16::CAPACITY, 16);
+    assert_eq!(Top8::new(0).capacity(), 8);
+    assert_eq!(TopN::<25>::new(0).capacity(), 25);
+    let mut it = TopN::<5>::new(0);
+    it.see_all(1..100);
+    assert_eq!(it.len(), it.capacity());
+}
+
+// A reader that returns at most three bytes at a time, to split values across reads.
+#[cfg(feature = "std")]
+struct Trickle<'a>(&'a [u8]);
+
+#[cfg(feature = "std")]
+impl std::io::Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.0.len()).min(3);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn see_from_reader() {
+    let data: Vec<u32> = (0..3000).map(|i| i * 7919 % 10007).collect();
+    let bytes: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+    let mut it = Top16::new(0);
+    assert_eq!(it.see_from_reader(&mut &bytes[..]).unwrap(), 3000);
+    assert_eq!(it, Top16::from(&data[..]));
+
+    let mut it = Top16::new(0);
+    assert_eq!(it.see_from_reader(&mut Trickle(&bytes[..40])).unwrap(), 10);
+    assert_eq!(it, Top16::from(&data[..10]));
+
+    // A partial value at the end is an error, but what came before it was seen.
+    let mut it = Top16::new(0);
+    let err = it.see_from_reader(&mut &bytes[..42]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert_eq!(it, Top16::from(&data[..10]));
+}
+
+#[test]
+fn max_exceeds_and_min_below() {
+    let mut it = Top8::new(0);
+    assert!(!it.max_exceeds(0));
+    assert!(!it.min_below(u32::MAX));
+    it.see_all([10, 20]);
+    assert!(it.max_exceeds(19));
+    assert!(!it.max_exceeds(20));
+    assert!(it.min_below(11));
+    assert!(!it.min_below(10));
+    let tops = [Top8::from(&[5][..]), Top8::from(&[50][..])];
+    assert_eq!(tops.iter().filter(|t| t.max_exceeds(30)).count(), 1);
+}
+
+proptest! {
+    #[test]
+    fn proptest_u16_matches_u32(data in prop::collection::vec(any::<u16>(), 0..300), cutoff in any::<u16>()) {
+        let mut small = TopFew::<u16>::new(cutoff);
+        let mut large = Top16::new(cutoff.into());
+        for &v in &data {
+            prop_assert_eq!(small.rank(v), large.rank(v.into()));
+        }
+        prop_assert!(small.iter().map(u32::from).eq(large.iter()));
+    }
+}
+
+#[test]
+fn u16_is_smaller() {
+    // The elements take half the space; the indices are a u64 either way.
+    assert!(std::mem::size_of::<TopFew<u16>>() < std::mem::size_of::<Top16>());
+    assert!(std::mem::size_of::<TopFew<u16>>() >= 16 * 2 + 8);
+}
+
+#[cfg(feature = "counters")]
+#[test]
+fn max_ever() {
+    let mut it = Top8::new(10);
+    it.see(5);
+    assert_eq!(it.max_ever(), None);
+    it.see_all([20, 50, 30]);
+    assert_eq!(it.max_ever(), Some(50));
+    it.retain(|v| v < 50);
+    assert_eq!((it.max(), it.max_ever()), (Some(30), Some(50)));
+
+    let mut band = top_few::TopFewBand::<u32, 8>::new(10, 40);
+    band.see_all([20, 60, 30]);
+    assert_eq!(
+        (band.max(), band.as_top_few().max_ever()),
+        (Some(30), Some(60))
+    );
+    it.merge(band.as_top_few());
+    assert_eq!(it.max_ever(), Some(60));
+    it.reinit(0);
+    assert_eq!(it.max_ever(), None);
+}
+
+#[test]
+fn resort() {
+    let mut it = Top8::new(10);
+    it.see_all([20, 30, 40, 50]);
+    let elements = it.elements_unordered_mut();
+    for v in elements.iter_mut() {
+        *v = match *v {
+            20 => 60,
+            30 => 5,
+            10 => 15,
+            v => v,
+        };
+    }
+    it.resort();
+    assert_eq!(it.check_invariants(), Ok(()));
+    // The 5 is not larger than the cutoff, so it is dropped.
+    assert_eq!(it.to_sorted_vec(), [60, 50, 40, 15, 15, 15, 15]);
+    it.see(16);
+    assert_eq!(it.to_sorted_vec(), [60, 50, 40, 16, 15, 15, 15, 15]);
+}
+
+#[test]
+fn to_array() {
+    let mut it = TopN::<5>::new(1);
+    it.see_all([7, 3, 9]);
+    assert_eq!(it.to_array_desc(), [9, 7, 3, 1, 1]);
+    assert_eq!(it.to_array_asc(), [1, 1, 3, 7, 9]);
+    it.see_all([8, 2, 6]);
+    assert_eq!(it.to_array_desc(), [9, 8, 7, 6, 3]);
+}
+
+#[test]
+fn with_seen_in_a_cell() {
+    let cell = std::cell::Cell::new(Top8::new(0));
+    let share = &cell;
+    for v in [4, 8, 2] {
+        share.set(share.get().with_seen(v));
+    }
+    assert_eq!(cell.get().to_sorted_vec(), [8, 4, 2]);
+    // The original is left alone.
+    let top = Top8::new(5);
+    assert_eq!(top.with_seen(3).with_seen(6).to_sorted_vec(), [6]);
+    assert!(top.is_empty());
+}
+
+#[test]
+fn iter_with_tie_flags() {
+    let mut it = Top8::new(0);
+    assert_eq!(it.iter_with_tie_flags().count(), 0);
+    it.see_all([5, 9, 5, 5, 2, 9]);
+    assert_eq!(
+        it.iter_with_tie_flags().collect::<Vec<_>>(),
+        [
+            (9, false),
+            (9, true),
+            (5, false),
+            (5, true),
+            (5, true),
+            (2, false)
+        ]
+    );
+}
+
+#[test]
+fn debug_string() {
+    let mut it = Top16::new(3);
+    assert_eq!(it.to_debug_string(), "cutoff=3;values=");
+    assert_eq!(Top16::from_debug_string("cutoff=3;values="), Ok(it));
+    it.see_all([20, 5, 19, 3, 18, 20]);
+    let s = it.to_debug_string();
+    assert_eq!(s, "cutoff=3;values=20,20,19,18,5");
+    assert_eq!(Top16::from_debug_string(&s), Ok(it));
+    // Hand-edited: reordered, spaced out, and with a value that is not above the cutoff.
+    assert_eq!(
+        Top16::from_debug_string(" cutoff=3;values=5, 18 ,19,20,20,2\n"),
+        Ok(it)
+    );
+}
+
+#[test]
+fn debug_string_errors() {
+    assert_eq!(Top16::from_debug_string(""), Err(ParseError::Format));
+    assert_eq!(
+        Top16::from_debug_string("values=1;cutoff=0"),
+        Err(ParseError::Format)
+    );
+    assert_eq!(
+        Top16::from_debug_string("cutoff=0,values=1"),
+        Err(ParseError::Format)
+    );
+    assert!(matches!(
+        Top16::from_debug_string("cutoff=-1;values=1"),
+        Err(ParseError::Value(_))
+    ));
+    let err = Top16::from_debug_string("cutoff=0;values=1,,2").unwrap_err();
+    assert!(matches!(err, ParseError::Value(_)));
+    assert!(err.to_string().starts_with("invalid number"));
+}
+
+proptest! {
+    #[test]
+    fn debug_string_round_trip(cutoff in 0u32..100, values: Vec<u32>) {
+        let mut it = TopN::<8>::new(cutoff);
+        it.see_all(values);
+        let back = TopN::<8>::from_debug_string(&it.to_debug_string()).unwrap();
+        prop_assert_eq!(back, it);
+        prop_assert_eq!(back.check_invariants(), Ok(()));
+    }
+}
+
+#[test]
+fn scoped() {
+    let mut it = TopN::<4>::new(3);
+    for round in 0..3 {
+        let mut scope = it.scoped();
+        assert!(scope.is_empty());
+        scope.see_all([5, 5, 10 + round, 2]);
+        assert_eq!(scope.iter().collect::<Vec<_>>(), [10 + round, 5, 5]);
+        // Only for the rest of this scope.
+        scope.set_cutoff(6);
+        assert_eq!(scope.cutoff(), 6);
+    }
+    assert!(it.is_empty());
+    assert_eq!(it.cutoff(), 3);
+}
+
+#[test]
+fn overlap() {
+    let mut a = Top16::new(0);
+    let mut b = Top16::new(0);
+    assert_eq!(a.overlap(&b), 0);
+    a.see_all([5, 5, 5, 3]);
+    b.see_all([5, 5, 4]);
+    assert_eq!(a.overlap(&b), 2);
+    assert_eq!(b.overlap(&a), 2);
+    assert_eq!(a.set_overlap(&b), 1);
+    assert_eq!(a.overlap(&a), 4);
+    assert_eq!(a.set_overlap(&a), 2);
+    b.see(1);
+    assert_eq!(a.overlap(&b), 2);
+    // Min TopFews walk down in ascending order.
+    let mut a = TopFew::<u32, 8, Min>::new(100);
+    let mut b = TopFew::<u32, 8, Min>::new(100);
+    a.see_all([1, 2, 2, 9]);
+    b.see_all([2, 2, 3, 9, 9]);
+    assert_eq!(a.overlap(&b), 3);
+    assert_eq!(a.set_overlap(&b), 2);
+}
+
+proptest! {
+    #[test]
+    fn overlap_matches_multiset(xs in prop::collection::vec(1u32..10, 0..30),
+                                ys in prop::collection::vec(1u32..10, 0..30)) {
+        let mut a = Top8::new(0);
+        let mut b = Top8::new(0);
+        a.see_all(xs);
+        b.see_all(ys);
+        let mut expected = 0;
+        let mut distinct = 0;
+        for v in 1..10 {
+            let (x, y) = (a.iter().filter(|&u| u == v).count(), b.iter().filter(|&u| u == v).count());
+            expected += x.min(y);
+            distinct += usize::from(x > 0 && y > 0);
+        }
+        prop_assert_eq!(a.overlap(&b), expected);
+        prop_assert_eq!(a.set_overlap(&b), distinct);
+    }
+}
+
+#[test]
+fn classify() {
+    let mut it = TopN::<2>::new(3);
+    assert_eq!(it.classify(3), SeeOutcome::Rejected);
+    assert_eq!(it.classify(10), SeeOutcome::AddedNotFull { position: 2 });
+    assert_eq!(it.classify(5), SeeOutcome::AddedNotFull { position: 1 });
+    assert_eq!(it.classify(5), SeeOutcome::Rejected);
+    let outcome = it.classify(20);
+    assert_eq!(
+        outcome,
+        SeeOutcome::Admitted {
+            position: 2,
+            evicted: 5
+        }
+    );
+    assert_eq!(outcome.position(), 2);
+    assert_eq!(outcome.evicted(), Some(5));
+    assert_eq!(SeeOutcome::<u32>::Rejected.position(), 0);
+    assert_eq!(
+        SeeOutcome::<u32>::AddedNotFull { position: 1 }.evicted(),
+        None
+    );
+    assert_eq!(it.iter().collect::<Vec<_>>(), [20, 10]);
+}
+
+proptest! {
+    #[test]
+    fn classify_agrees_with_insert(values: Vec<u32>) {
+        let mut a = Top8::new(0);
+        let mut b = Top8::new(0);
+        for v in values {
+            let outcome = a.classify(v);
+            let result = b.insert(v);
+            prop_assert_eq!(outcome.position(), result.rank);
+            prop_assert_eq!(outcome.evicted(), result.evicted);
+            prop_assert_eq!(matches!(outcome, SeeOutcome::AddedNotFull { .. }),
+                result.rank != 0 && result.evicted.is_none());
+        }
+    }
+}
+
+#[test]
+fn set_min_threshold() {
+    let mut it = TopN::<4>::new(0);
+    it.see_all([1, 2]);
+    // Lowering does nothing.
+    it.set_min_threshold(0);
+    assert_eq!(it.threshold(), 0);
+    it.set_min_threshold(10);
+    assert_eq!(it.threshold(), 10);
+    assert_eq!(it.cutoff(), 0);
+    assert_eq!(it.check_invariants(), Ok(()));
+    assert!(!it.would_admit(10));
+    assert_eq!(it.classify(5), SeeOutcome::Rejected);
+    // Not full, so nothing is evicted, and the threshold goes back to the cutoff.
+    assert_eq!(it.classify(11), SeeOutcome::AddedNotFull { position: 4 });
+    assert_eq!(it.threshold(), 0);
+    assert!(!it.can_lower_cutoff());
+    assert_eq!(it.iter().collect::<Vec<_>>(), [11, 2, 1]);
+    // Once it is full, an admission evicts the real minimum.
+    it.see(3);
+    it.set_min_threshold(20);
+    assert_eq!(
+        it.classify(21),
+        SeeOutcome::Admitted {
+            position: 4,
+            evicted: 1
+        }
+    );
+    assert_eq!(it.threshold(), 2);
+    assert_eq!(it.check_invariants(), Ok(()));
+    // Changing the cutoff puts the natural threshold back.
+    it.set_min_threshold(20);
+    it.set_cutoff(1);
+    assert_eq!(it.threshold(), 2);
+}
+
+proptest! {
+    #[test]
+    fn see_slice_same_as_see(values in prop::collection::vec(0u32..50, 0..100), cutoff in 0u32..10) {
+        let mut a = Top16::new(cutoff);
+        let mut b = a;
+        let mut admitted = 0;
+        for &v in &values {
+            admitted += usize::from(b.rank(v) != 0);
+        }
+        prop_assert_eq!(a.see_slice(&values), admitted);
+        prop_assert_eq!(a, b);
+        let mut a = TopFew::<u32, 16, NewestWins>::new(cutoff);
+        let mut b = a;
+        let mut admitted = 0;
+        for &v in &values {
+            admitted += usize::from(b.rank(v) != 0);
+        }
+        prop_assert_eq!(a.see_slice(&values), admitted);
+        prop_assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn distinct_len() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.distinct_len(), 0);
+    it.see_all([7; 20]);
+    assert_eq!(it.len(), 16);
+    assert_eq!(it.distinct_len(), 1);
+    it.see_all([8, 9, 9]);
+    assert_eq!(it.distinct_len(), 3);
+}
+
+proptest! {
+    #[test]
+    fn distinct_len_matches_hash_set(values in prop::collection::vec(0u32..10, 0..40)) {
+        let mut it = Top8::new(0);
+        it.see_all(values);
+        prop_assert_eq!(it.distinct_len(), it.iter().collect::<HashSet<_>>().len());
+    }
+}
+
+#[test]
+fn max_multiplicity() {
+    let mut it = Top16::new(0);
+    assert_eq!(it.max_multiplicity(), 0);
+    it.see_all([3, 5, 4]);
+    assert_eq!(it.max_multiplicity(), 1);
+    it.see_all([5, 5]);
+    assert_eq!(it.max_multiplicity(), 3);
+    it.see_all([5; 20]);
+    assert_eq!(it.max_multiplicity(), 16);
+    assert_eq!(it.max_multiplicity(), it.count_equal(5));
+}
+
+#[test]
+fn iter_above() {
+    let mut it = Top16::new(2);
+    assert_eq!(it.iter_above(0).count(), 0);
+    it.see_all([3, 9, 5, 5, 7]);
+    assert_eq!(it.iter_above(5).collect::<Vec<_>>(), [9, 7]);
+    assert_eq!(it.iter_above(4).rev().collect::<Vec<_>>(), [5, 5, 7, 9]);
+    assert_eq!(it.iter_above(4).len(), 4);
+    assert_eq!(it.iter_above(9).next(), None);
+    // A bound below the cutoff still leaves out the unfilled slots.
+    assert_eq!(
+        it.iter_above(0).collect::<Vec<_>>(),
+        it.iter().collect::<Vec<_>>()
+    );
+    // For a Min TopFew, "above" means ranking higher, i.e. smaller.
+    let mut it = TopFew::<u32, 8, Min>::new(100);
+    it.see_all([3, 9, 5]);
+    assert_eq!(it.iter_above(6).collect::<Vec<_>>(), [3, 5]);
+}
+
+proptest! {
+    #[test]
+    fn iter_above_matches_filter(values in prop::collection::vec(0u32..30, 0..40), bound in 0u32..30) {
+        let mut it = Top8::new(3);
+        it.see_all(values);
+        let expected: Vec<u32> = it.iter().filter(|&v| v > bound).collect();
+        prop_assert_eq!(it.iter_above(bound).collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn set_cutoff_undoes_min_threshold_when_full() {
+    let mut it = TopN::<4>::new(0);
+    it.see_all([5, 6, 7, 8]);
+    it.set_min_threshold(20);
+    it.set_cutoff(0);
+    assert_eq!(it.threshold(), 5);
+    it.see(9);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [9, 8, 7, 6]);
+}
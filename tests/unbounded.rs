@@ -0,0 +1,74 @@
+use proptest::prelude::*;
+use top_few::{Top16, TopFew, TopFewUnbounded};
+
+#[test]
+fn all_zeros() {
+    let mut it = TopFewUnbounded::new();
+    assert!(it.is_empty());
+    assert_eq!(it.max(), None);
+    for i in 0..16 {
+        assert_eq!(it.rank(0), 16 - i);
+    }
+    assert_eq!(it.rank(0), 0);
+    assert_eq!(it.len(), 16);
+    assert_eq!(it.max(), Some(0));
+    assert_eq!(it.iter().collect::<Vec<_>>(), [0; 16]);
+}
+
+#[test]
+fn nonzero_values_push_out_zeros() {
+    let mut it = TopFewUnbounded::new();
+    for _ in 0..10 {
+        it.see(0);
+    }
+    for v in 1..=8 {
+        it.see(v);
+    }
+    assert_eq!(it.len(), 16);
+    let mut expected: Vec<u32> = (1..=8).rev().collect();
+    expected.extend([0; 8]);
+    assert_eq!(it.iter().collect::<Vec<_>>(), expected);
+    assert_eq!(it.iter().next_back(), Some(0));
+    assert_eq!(it.rank(0), 0);
+}
+
+#[test]
+fn from_top16() {
+    let mut it = Top16::unbounded();
+    it.see(0);
+    it.see(3);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [3, 0]);
+}
+
+#[test]
+fn signed_minimums() {
+    let mut it = TopFew::<i32>::unbounded();
+    for v in [i32::MIN, -5, i32::MIN, 7] {
+        it.see(v);
+    }
+    assert_eq!(it.iter().collect::<Vec<_>>(), [7, -5, i32::MIN, i32::MIN]);
+    assert_eq!(it.max(), Some(7));
+    let mut it = TopFewUnbounded::<i8>::new();
+    for _ in 0..20 {
+        it.see(i8::MIN);
+    }
+    assert_eq!(it.len(), 16);
+    assert_eq!(it.max(), Some(i8::MIN));
+}
+
+proptest! {
+    #[test]
+    fn matches_sorting(data in prop::collection::vec(0..4u32, 0..40)) {
+        // A TopFew<Option<u32>> with a cutoff of None also retains 0s.
+        let mut it = TopFewUnbounded::new();
+        let mut options = TopFew::<Option<u32>>::new(None);
+        for &v in &data {
+            prop_assert_eq!(it.rank(v), options.rank(Some(v)));
+        }
+        let mut expected = data.clone();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        expected.truncate(16);
+        prop_assert_eq!(it.len(), expected.len());
+        prop_assert_eq!(it.iter().collect::<Vec<_>>(), expected);
+    }
+}
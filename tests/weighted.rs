@@ -0,0 +1,51 @@
+use proptest::prelude::*;
+use std::cmp::Reverse;
+use top_few::TopFewWeighted;
+
+#[test]
+fn evictions_drop_weights() {
+    let mut it = TopFewWeighted::new(0);
+    for v in 1..=16 {
+        it.see(v, v * 10);
+    }
+    assert_eq!(it.total_weight(), (1..=16).map(|v| v * 10).sum::<u64>());
+    // Pushes out 1, with weight 10.
+    it.see(100, 7);
+    assert_eq!(it.total_weight(), (2..=16).map(|v| v * 10).sum::<u64>() + 7);
+    // Not admitted, so its weight does not count.
+    it.see(1, 1000);
+    assert_eq!(it.total_weight(), (2..=16).map(|v| v * 10).sum::<u64>() + 7);
+    assert_eq!(it.iter().next(), Some((100, 7)));
+}
+
+#[test]
+fn large_weights() {
+    let mut it = TopFewWeighted::new(0);
+    for v in 1..=20 {
+        it.see(v, u32::MAX);
+    }
+    assert_eq!(it.total_weight(), 16 * u64::from(u32::MAX));
+}
+
+proptest! {
+    #[test]
+    fn total_matches_retained(pairs in prop::collection::vec((0u32..20, any::<u32>()), 0..60)) {
+        let mut it = TopFewWeighted::new(0);
+        for &(v, w) in &pairs {
+            it.see(v, w);
+        }
+        let total: u64 = it.iter().map(|(_, w)| u64::from(w)).sum();
+        prop_assert_eq!(it.total_weight(), total);
+        // The retained values are the top ones, oldest first among ties,
+        // each with the weight it was seen with.  The stable sort keeps the oldest
+        // of the values that tie at the boundary, and then both sides are sorted
+        // by weight too, so the order within a run of equal values does not matter.
+        let mut expected: Vec<(u32, u32)> = pairs.iter().copied().filter(|&(v, _)| v > 0).collect();
+        expected.sort_by_key(|&(v, _)| Reverse(v));
+        expected.truncate(16);
+        expected.sort_by_key(|&(v, w)| (Reverse(v), w));
+        let mut actual: Vec<(u32, u32)> = it.iter().collect();
+        actual.sort_by_key(|&(v, w)| (Reverse(v), w));
+        prop_assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,45 @@
+use top_few::TopFewWindow;
+
+#[test]
+fn old_values_expire() {
+    let mut it = TopFewWindow::new(0, 3);
+    for i in [10, 9, 8, 1] {
+        it.see(i);
+    }
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [9, 8, 1]);
+    it.see(2);
+    it.see(3);
+    let elements: Vec<u32> = it.iter().collect();
+    assert_eq!(elements, [3, 2, 1]);
+}
+
+#[test]
+fn expiry_makes_room() {
+    let mut it = TopFewWindow::new(0, 20);
+    for i in (100..116).rev() {
+        it.see(i); // Fill it up.
+    }
+    for i in 1..=20 {
+        it.see(i);
+    }
+    // The window now holds 1 through 20, and the first 16 values have expired,
+    // except that 1 through 4 were seen while the list was still full of them.
+    let elements: Vec<u32> = it.iter().collect();
+    let expected: Vec<u32> = (5..=20).rev().collect();
+    assert_eq!(elements, expected);
+}
+
+#[test]
+#[should_panic(expected = "window of 0")]
+fn empty_window_panics() {
+    TopFewWindow::new(0, 0);
+}
+
+#[test]
+fn window_of_one() {
+    let mut it = TopFewWindow::new(0, 1);
+    it.see(5);
+    it.see(3);
+    assert_eq!(it.iter().collect::<Vec<_>>(), [3]);
+}